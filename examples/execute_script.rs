@@ -1,12 +1,12 @@
-use privacy_sexy::{get_collection, run_script, OS::Windows};
+use privacy_sexy::prelude::*;
 
 fn main() {
     // Get CollectionData for Windows
-    let coll = get_collection(Windows).unwrap();
+    let coll = get_collection(OS::Windows).unwrap();
 
     // Parse CollectionData to string
     let script = coll.parse(None, false, None).unwrap();
 
     // Execute script
-    run_script(&script, coll.scripting.file_extension).unwrap();
+    run_script(&script, coll.file_extension().map(String::from)).unwrap();
 }
@@ -1,8 +1,8 @@
-use privacy_sexy::{collection::Recommend, get_collection, OS::Windows};
+use privacy_sexy::prelude::*;
 
 fn main() {
     // Get CollectionData for Windows
-    let coll = get_collection(Windows).unwrap();
+    let coll = get_collection(OS::Windows).unwrap();
 
     // Get Strict script
     let script = coll.parse(None, false, Some(Recommend::Strict)).unwrap();
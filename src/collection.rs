@@ -1,15 +1,33 @@
-use std::{fs::File, io, path::Path};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    env, fmt,
+    fs::{self, File},
+    io::{self, Read, Write},
+    mem,
+    path::Path,
+    process::ExitStatus,
+    ptr, slice,
+    sync::Arc,
+};
 
+use log::{debug, info, warn};
 use regex::{Captures, Regex};
-use reqwest::{blocking::get, IntoUrl};
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use thiserror::Error;
+use url::Url;
 
 use crate::{
-    util::{beautify, parse_start_end, piper},
+    renderer::{self, ScriptRenderer},
+    run_script_with,
+    util::{beautify, category_header, function_name, parse_start_end, piper, BOM, SUDO_CHECK},
     OS,
 };
 
+#[cfg(feature = "network")]
+pub use crate::network::DEFAULT_TIMEOUT;
+
 /// Error type emitted during parsing
 #[derive(Debug)]
 pub enum ParseError {
@@ -19,6 +37,185 @@ pub enum ParseError {
     Parameter(String),
     /// Emitted when neither call or code are not provided, with the name of the [`ScriptData`]
     CallCode(String),
+    /// Emitted when a function name is not unique, with the name of the [`FunctionData`]
+    DuplicateFunction(String),
+    /// Emitted when a pipe fails to apply, with the name of the pipe
+    Pipe(String),
+    /// Emitted when a pipe is not one of the available pipes, with the name of the pipe
+    UnknownPipe(String),
+    /// Emitted when requested scripts are not found, with their names
+    UnknownNames(Vec<String>),
+    /// Emitted when an environment variable used by an `env` expression is not set, with the name of the variable
+    EnvVar(String),
+    /// Emitted when a parameter value is not a string, number, boolean or null, with the name of the
+    /// [`ParameterDefinitionData`]
+    ParameterValue(String),
+    /// Emitted when a selected script has no `docs` and [`ParseOptions::require_docs`] is set, with the name of the
+    /// [`ScriptData`]
+    MissingDocs(String),
+    /// Emitted when writing the parsed script fails, refer to [`CollectionData::write_parsed`]
+    IOError(io::Error),
+    /// Emitted when scripts require each other, with the names of the [`ScriptData`] forming the cycle
+    DependencyCycle(Vec<String>),
+    /// Emitted when functions call each other, with the names of the [`FunctionData`] forming the cycle
+    CallCycle(Vec<String>),
+}
+
+/// Error type emitted during validation, refer to [`CollectionData::validate`]
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    /// Emitted when the name of a [`ScriptData`] is not unique, with the name
+    #[error("script name `{0}` is not unique")]
+    DuplicateScript(String),
+    /// Emitted when the name of a [`CategoryData`] is not unique, with the name
+    #[error("category name `{0}` is not unique")]
+    DuplicateCategory(String),
+    /// Emitted when the name of a [`FunctionData`] is not unique, with the name
+    #[error("function name `{0}` is not unique")]
+    DuplicateFunction(String),
+    /// Emitted when a called function is not defined, with the name of the [`FunctionData`]
+    #[error("function `{0}` is called but not defined")]
+    UnknownFunction(String),
+    /// Emitted when a [`ScriptData`] or [`FunctionData`] defines both or neither of `code` and `call`, with its name
+    #[error("`{0}` must define exactly one of `code` or `call`")]
+    CodeCallConflict(String),
+    /// Emitted when a parameter of a [`FunctionData`] is never used, with the names of the function and parameter
+    #[error("parameter `{1}` of function `{0}` is never used")]
+    UnusedParameter(String, String),
+    /// Emitted when a [`FunctionData`] uses a parameter it does not declare, with the names of the function and
+    /// parameter
+    #[error("function `{0}` uses undeclared parameter `{1}`")]
+    UndeclaredParameter(String, String),
+    /// Emitted when a documentation URL of a [`ScriptData`] is not a valid URL, entries containing whitespace are
+    /// markdown documentation and are not checked
+    #[error("script `{script}` has invalid documentation URL `{url}`")]
+    InvalidDocUrl {
+        /// Name of the [`ScriptData`]
+        script: String,
+        /// The invalid URL
+        url: String,
+    },
+    /// Emitted when a call does not provide a parameter that the called [`FunctionData`] requires, i.e. that is
+    /// neither `optional` nor has a `default`
+    #[error("call to function `{call}` is missing required parameter `{param}`")]
+    MissingCallParameter {
+        /// Name of the called [`FunctionData`]
+        call: String,
+        /// Name of the missing [`ParameterDefinitionData`]
+        param: String,
+    },
+}
+
+impl ValidationError {
+    /// Returns the section title of [`ValidationReport`] and the name of the script or function of the error
+    fn section(&self) -> (&'static str, &str) {
+        match self {
+            ValidationError::DuplicateScript(name) => ("Duplicate scripts", name),
+            ValidationError::DuplicateCategory(name) => ("Duplicate categories", name),
+            ValidationError::DuplicateFunction(name) => ("Duplicate functions", name),
+            ValidationError::UnknownFunction(name) => ("Unknown functions", name),
+            ValidationError::CodeCallConflict(name) => ("Code and call conflicts", name),
+            ValidationError::UnusedParameter(name, _) => ("Unused parameters", name),
+            ValidationError::UndeclaredParameter(name, _) => ("Undeclared parameters", name),
+            ValidationError::InvalidDocUrl { script, .. } => ("Invalid documentation URLs", script),
+            ValidationError::MissingCallParameter { call, .. } => ("Missing call parameters", call),
+        }
+    }
+}
+
+/**
+Human-friendly report of [`ValidationError`]s, displayed grouped in a section per type of error and sorted by
+the name of the script or function of each error
+
+# Examples
+
+```ignore
+Duplicate scripts (1):
+  - script name `Script` is not unique
+Unknown functions (1):
+  - function `NoSuchFunction` is called but not defined
+```
+*/
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Errors to report
+    pub errors: Vec<ValidationError>,
+}
+
+impl From<Vec<ValidationError>> for ValidationReport {
+    fn from(errors: Vec<ValidationError>) -> Self {
+        ValidationReport { errors }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sections: Vec<(&str, Vec<&ValidationError>)> = Vec::new();
+        for e in &self.errors {
+            let (title, _) = e.section();
+            match sections.iter_mut().find(|(t, _)| *t == title) {
+                Some((_, errors)) => errors.push(e),
+                None => sections.push((title, vec![e])),
+            }
+        }
+
+        for (title, mut errors) in sections {
+            errors.sort_by_key(|e| e.section().1);
+            writeln!(f, "{title} ({}):", errors.len())?;
+            for e in errors {
+                writeln!(f, "  - {e}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Emitted when running scripts of a [`CollectionData`] fails, refer to [`CollectionData::run_each`]
+#[derive(Debug, Error)]
+pub enum RunError {
+    /// Refer to [`ParseError`]
+    #[error("failed to parse script: {0:?}")]
+    ParseError(ParseError),
+    /// Refer to [`io::Error`]
+    #[error(transparent)]
+    IOError(#[from] io::Error),
+}
+
+impl From<ParseError> for RunError {
+    fn from(err: ParseError) -> Self {
+        RunError::ParseError(err)
+    }
+}
+
+/// Emitted when the `schemaVersion` of a [`CollectionData`] is not supported, refer to
+/// [`CollectionData::check_schema_version`]
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    /// Emitted when a version is not of the form `MAJOR.MINOR.PATCH`, with the version
+    #[error("invalid schema version `{0}`")]
+    InvalidVersion(String),
+    /// Emitted when the collection is written for an incompatible major version
+    #[error("collection schema version `{found}` is incompatible with supported version `{supported}`")]
+    Incompatible {
+        /// `schemaVersion` of the [`CollectionData`]
+        found: String,
+        /// Version supported by the caller
+        supported: String,
+    },
+}
+
+/// Latest version of the collection format supported by this crate, refer to [`CollectionData::schema_version`]
+pub const SCHEMA_VERSION: &str = "1.0.0";
+
+/// Parses `version` as `MAJOR.MINOR.PATCH`, ignoring any pre-release or build metadata
+fn parse_version(version: &str) -> Result<(u64, u64, u64), SchemaError> {
+    let invalid = || SchemaError::InvalidVersion(version.to_string());
+    let core = version.trim().split(['-', '+']).next().unwrap_or_default();
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().map_err(|_| invalid()));
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(major), Some(minor), Some(patch), None) => Ok((major?, minor?, patch?)),
+        _ => Err(invalid()),
+    }
 }
 
 /**
@@ -29,12 +226,23 @@ pub enum ParseError {
   - OS specific details
 - Also allows defining common [function](FunctionData)s to be used throughout the collection if
   you'd like different scripts to share same code.
+- 💡 YAML anchors (`&name`) and aliases (`*name`) are expanded while reading, e.g. to share a `code` block or
+  `parameters` between scripts. Merge keys (`<<: *name`) are not supported.
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CollectionData {
     /// - Operating system that the [Collection](CollectionData) is written for.
     /// - 📖 See [crate](OS) enum for allowed values.
     pub os: OS,
+    /// - Optional human-facing name of the [Collection](CollectionData), e.g. `Privacy tweaks for macOS`
+    pub title: Option<String>,
+    /// - Optional human-facing summary of what the [Collection](CollectionData) does
+    pub description: Option<String>,
+    /// - Optional version of the collection format the [Collection](CollectionData) is written for, e.g. `1.0.0`
+    /// - 💡 Allows tools to reject collections written for an incompatible format, refer to
+    ///   [`CollectionData::check_schema_version`]
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: Option<String>,
     /// - Defines the scripting language that the code of other action uses.
     pub scripting: ScriptingDefinitionData,
     /// - Each [category](CategoryData) is rendered as different cards in card presentation.
@@ -42,6 +250,28 @@ pub struct CollectionData {
     pub actions: Vec<CategoryData>,
     /// - Functions are optionally defined to re-use the same code throughout different scripts.
     pub functions: Option<Vec<FunctionData>>,
+    /**
+    - Paths of YAML files defining shared `functions`, relative to the collection file, e.g.
+
+      ```yaml
+      imports:
+        - shared/functions.yaml
+      ```
+
+    - Imported functions are merged into `functions` by the loaders reading from a path, i.e.
+      [`CollectionData::from_file`], [`CollectionData::from_file_strict`], [`CollectionData::from_combined_file`]
+      and [`CollectionData::from_dir`], which then clear `imports`
+    - [`CollectionData::from_reader`] & collections fetched over the network have no directory to resolve
+      `imports` against, so they are kept as is
+    - ❗ Imported function names must not collide with other functions
+    */
+    pub imports: Option<Vec<String>>,
+}
+
+/// File imported by [`CollectionData::imports`]
+#[derive(Deserialize)]
+struct FunctionsFile {
+    functions: Vec<FunctionData>,
 }
 
 /// Emitted when reading [`CollectionData`] from file fails
@@ -54,73 +284,1398 @@ pub enum CollectionError {
     #[error(transparent)]
     SerdeError(#[from] serde_yaml::Error),
     /// Refer to [`reqwest::Error`]
+    #[cfg(feature = "network")]
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
+    /// Emitted when a file does not contain a collection for the requested [`OS`]
+    #[error("no collection found for {0}")]
+    OSNotFound(OS),
+    /// Emitted in strict mode when a key is not a known field, with the path to the key
+    #[error("unknown field `{0}`")]
+    UnknownField(String),
+    /// Emitted when an imported [`FunctionData`] has the same name as another function, with the name
+    #[error("imported function `{0}` is already defined")]
+    DuplicateFunction(String),
+}
+
+/// Appends the scripts required by `entry` in `all` and then `entry` to `ordered` unless `done`, with `path` being
+/// the names of the scripts requiring `entry`
+fn order_requires<'a>(
+    entry: (usize, &'a ScriptData),
+    all: &HashMap<&str, (usize, &'a ScriptData)>,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+    ordered: &mut Vec<(usize, &'a ScriptData)>,
+) -> Result<(), ParseError> {
+    let (_, script) = entry;
+    if done.contains(script.name.as_str()) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|name| *name == script.name) {
+        let mut cycle = path[start..].iter().map(ToString::to_string).collect::<Vec<_>>();
+        cycle.push(script.name.clone());
+        return Err(ParseError::DependencyCycle(cycle));
+    }
+
+    path.push(&script.name);
+    for name in script.requires.iter().flatten() {
+        let required = *all
+            .get(name.as_str())
+            .ok_or_else(|| ParseError::UnknownNames(vec![name.clone()]))?;
+        order_requires(required, all, path, done, ordered)?;
+    }
+    path.pop();
+
+    done.insert(&script.name);
+    ordered.push(entry);
+    Ok(())
+}
+
+/// Appends the scripts requiring `entry` in `dependents` and then `entry` to `ordered` unless `done`, the reverse of
+/// [`order_requires`] for reverting
+fn order_dependents<'a>(
+    entry: (usize, &'a ScriptData),
+    dependents: &HashMap<&str, Vec<(usize, &'a ScriptData)>>,
+    done: &mut HashSet<&'a str>,
+    ordered: &mut Vec<(usize, &'a ScriptData)>,
+) {
+    let (_, script) = entry;
+    if !done.insert(&script.name) {
+        return;
+    }
+    for dependent in dependents.get(script.name.as_str()).into_iter().flatten() {
+        order_dependents(*dependent, dependents, done, ordered);
+    }
+    ordered.push(entry);
 }
 
-impl CollectionData {
+/// Returns the depth of the call chain of `fd`, `1` if it calls no function, caching it in `depths`, with `path`
+/// being the names of the functions calling `fd`
+fn call_depth<'a>(
+    fd: &'a FunctionData,
+    funcs: &Functions<'a>,
+    path: &mut Vec<&'a str>,
+    depths: &mut HashMap<&'a str, usize>,
+) -> Result<usize, ParseError> {
+    if let Some(depth) = depths.get(fd.name.as_str()) {
+        return Ok(*depth);
+    }
+    if let Some(start) = path.iter().position(|name| *name == fd.name) {
+        let mut cycle = path[start..].iter().map(ToString::to_string).collect::<Vec<_>>();
+        cycle.push(fd.name.clone());
+        return Err(ParseError::CallCycle(cycle));
+    }
+
+    path.push(&fd.name);
+    let mut depth = 0;
+    for fcd in fd.call.iter().flat_map(FunctionCallsData::as_slice) {
+        let called = funcs
+            .get(fcd.function.as_str())
+            .ok_or_else(|| ParseError::Function(fcd.function.clone()))?;
+        depth = depth.max(call_depth(called, funcs, path, depths)?);
+    }
+    path.pop();
+
+    depths.insert(&fd.name, depth + 1);
+    Ok(depth + 1)
+}
+
+/// Finds the path to the first key in `value` that is missing from its deserialized and re-serialized `known` form
+fn unknown_field(value: &Value, known: &Value, path: String) -> Option<String> {
+    match (value, known) {
+        (Value::Mapping(map), Value::Mapping(known)) => map.iter().find_map(|(key, v)| {
+            let name = key.as_str().map_or_else(|| format!("{key:?}"), str::to_string);
+            let path = if path.is_empty() {
+                name
+            } else {
+                format!("{path}.{name}")
+            };
+            match known.get(key) {
+                Some(k) => unknown_field(v, k, path),
+                None => Some(path),
+            }
+        }),
+        (Value::Sequence(seq), Value::Sequence(known)) => seq
+            .iter()
+            .zip(known)
+            .enumerate()
+            .find_map(|(i, (v, k))| unknown_field(v, k, format!("{path}[{i}]"))),
+        _ => None,
+    }
+}
+
+impl TryFrom<&str> for CollectionData {
+    type Error = CollectionError;
+
+    /// Deserializes [`CollectionData`] from YAML `s`, as by [`CollectionData::from_reader`]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        CollectionData::from_reader(s.as_bytes())
+    }
+}
+
+impl CollectionData {
+    /**
+    Reads [`CollectionData`] from file at `path`, merging the functions of its `imports`
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - file or an imported file cannot be opened OR
+    - contents cannot be deserialized into [`CollectionData`] OR
+    - an imported function name is not unique, as [`CollectionError::DuplicateFunction`]
+    */
+    pub fn from_file(path: impl AsRef<Path>) -> Result<CollectionData, CollectionError> {
+        let path = path.as_ref();
+        CollectionData::from_reader(File::open(path)?)?.resolve_imports(path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Merges the functions of `imports`, relative to `dir`, into `functions`
+    fn resolve_imports(mut self, dir: &Path) -> Result<CollectionData, CollectionError> {
+        let Some(imports) = self.imports.take() else {
+            return Ok(self);
+        };
+
+        let functions = self.functions.get_or_insert_with(Vec::new);
+        let mut names = functions.iter().map(|fd| fd.name.clone()).collect::<HashSet<_>>();
+        for import in imports {
+            debug!("importing functions from {import}");
+            let file = serde_yaml::from_reader::<_, FunctionsFile>(File::open(dir.join(import))?)?;
+            for fd in file.functions {
+                if !names.insert(fd.name.clone()) {
+                    return Err(CollectionError::DuplicateFunction(fd.name));
+                }
+                functions.push(fd);
+            }
+        }
+        Ok(self)
+    }
+
+    /**
+    Reads [`CollectionData`] for `os` from file at `path`
+
+    The file can either be a single collection, or a combined bundle mapping each OS to its collection where
+    the `os` field of each collection can be omitted:
+
+    ```yaml
+    windows:
+      scripting: ...
+      actions: ...
+    macos:
+      scripting: ...
+      actions: ...
+    ```
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - file or an imported file cannot be opened OR
+    - file does not contain a collection for `os` OR
+    - contents cannot be deserialized into [`CollectionData`] OR
+    - an imported function name is not unique, as [`CollectionError::DuplicateFunction`]
+    */
+    pub fn from_combined_file(path: impl AsRef<Path>, os: OS) -> Result<CollectionData, CollectionError> {
+        let path = path.as_ref();
+        let mut value = serde_yaml::from_reader::<_, Value>(File::open(path)?)?;
+
+        let os_key = Value::from("os");
+        if value.get(&os_key).is_none() {
+            value = mem::take(value.get_mut(os.to_string()).ok_or(CollectionError::OSNotFound(os))?);
+            if let Value::Mapping(mapping) = &mut value {
+                mapping.entry(os_key).or_insert_with(|| Value::from(os.to_string()));
+            }
+        }
+
+        let cd = serde_yaml::from_value::<CollectionData>(value)?;
+        if cd.os == os {
+            cd.loaded().resolve_imports(path.parent().unwrap_or(Path::new("")))
+        } else {
+            Err(CollectionError::OSNotFound(os))
+        }
+    }
+
+    /**
+    Reads [`CollectionData`] from directory at `path`
+
+    The directory must contain a `collection.yaml` defining `os`, `scripting` and optionally `functions` and
+    `imports` (relative to the directory), and a
+    `categories/` folder where each `.yaml`/`.yml` file defines a single [category](CategoryData). Categories are
+    appended to the `actions` of `collection.yaml` (if any) in alphabetical order of their file names, so prefix
+    them with numbers (e.g. `01-privacy.yaml`) to control the order.
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - `collection.yaml`, a category file or an imported file cannot be read OR
+    - contents cannot be deserialized into [`CollectionData`] OR
+    - an imported function name is not unique, as [`CollectionError::DuplicateFunction`]
+    */
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<CollectionData, CollectionError> {
+        let path = path.as_ref();
+        let mut value = serde_yaml::from_reader::<_, Value>(File::open(path.join("collection.yaml"))?)?;
+
+        let mut files = fs::read_dir(path.join("categories"))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        files.retain(|f| f.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"));
+        files.sort();
+
+        let mut actions = match value.get_mut("actions").map(mem::take) {
+            Some(Value::Sequence(actions)) => actions,
+            _ => Vec::new(),
+        };
+        for file in files {
+            actions.push(serde_yaml::from_reader(File::open(file)?)?);
+        }
+        if let Value::Mapping(map) = &mut value {
+            map.insert("actions".into(), Value::Sequence(actions));
+        }
+
+        serde_yaml::from_value::<CollectionData>(value)?
+            .loaded()
+            .resolve_imports(path)
+    }
+
+    /**
+    Reads [`CollectionData`] from `reader`
+
+    # Errors
+
+    Returns [`CollectionError`] if contents cannot be read or deserialized into [`CollectionData`]
+    */
+    pub fn from_reader(reader: impl Read) -> Result<CollectionData, CollectionError> {
+        Ok(serde_yaml::from_reader::<_, CollectionData>(reader)?.loaded())
+    }
+
+    /**
+    Reads [`CollectionData`] from file at `path`, rejecting keys that are not known fields
+
+    Unlike [`CollectionData::from_file`], which ignores unknown keys for forward compatibility, this catches
+    typos such as `revertCde` that would otherwise silently drop data.
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - file or an imported file cannot be opened OR
+    - contents cannot be deserialized into [`CollectionData`] OR
+    - contents contain a key that is not a known field OR
+    - an imported function name is not unique, as [`CollectionError::DuplicateFunction`]
+    */
+    pub fn from_file_strict(path: impl AsRef<Path>) -> Result<CollectionData, CollectionError> {
+        let path = path.as_ref();
+        let value = serde_yaml::from_reader::<_, Value>(File::open(path)?)?;
+        let cd = serde_yaml::from_value::<CollectionData>(value.clone())?;
+        match unknown_field(&value, &serde_yaml::to_value(&cd)?, String::new()) {
+            Some(field) => Err(CollectionError::UnknownField(field)),
+            None => cd.loaded().resolve_imports(path.parent().unwrap_or(Path::new(""))),
+        }
+    }
+
+    /**
+    Serializes [`CollectionData`] into a YAML String
+
+    # Errors
+
+    Returns [`serde_yaml::Error`] if serialization fails
+    */
+    pub fn to_yaml_string(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /**
+    Writes [`CollectionData`] as YAML to file at `path`
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - serialization fails OR
+    - file cannot be written
+    */
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), CollectionError> {
+        fs::write(path, self.to_yaml_string()?)?;
+        Ok(())
+    }
+
+    /// Logs that [`CollectionData`] has been loaded
+    pub(crate) fn loaded(self) -> Self {
+        info!(
+            "loaded {} collection with {} scripts",
+            self.os,
+            self.iter_scripts().count()
+        );
+        self
+    }
+
+    /// Returns the scripting language of the collection, refer to [`ScriptingDefinitionData::language`]
+    pub fn language(&self) -> &str {
+        &self.scripting.language
+    }
+
+    /// Returns the file extension for scripts generated from the collection, if defined
+    ///
+    /// Refer to [`ScriptingDefinitionData::file_extension`]
+    pub fn file_extension(&self) -> Option<&str> {
+        self.scripting.file_extension.as_deref()
+    }
+
+    /**
+    Returns the file extension for scripts generated from the collection, falling back to one derived from the
+    scripting language if not defined, or from the [`OS`] if the language is not known:
+    - `powershell`: `ps1`
+    - `batchfile`: `bat`
+    - `shellscript` / `bash`: `sh`
+    - otherwise `bat` for [`OS::Windows`] and `sh` for the rest
+    */
+    pub fn effective_file_extension(&self) -> &str {
+        self.file_extension()
+            .unwrap_or(match (self.scripting.language.as_str(), self.os) {
+                ("powershell", _) => "ps1",
+                ("batchfile", _) => "bat",
+                ("shellscript" | "bash", _) => "sh",
+                (_, OS::Windows) => "bat",
+                _ => "sh",
+            })
+    }
+
+    /// Returns the first [`ScriptData`] named `name`, searching the `actions` tree recursively
+    pub fn find_script(&self, name: &str) -> Option<&ScriptData> {
+        self.actions.iter().find_map(|action| action.find_script(name))
+    }
+
+    /**
+    Returns the recommend level of the [`ScriptData`] named `name`, including one inherited from its
+    [categories](CategoryData)
+    - `None` if the script is not found
+    - `Some(None)` if the script is not recommended
+    */
+    pub fn recommend_of(&self, name: &str) -> Option<Option<Recommend>> {
+        self.recommends()
+            .into_iter()
+            .find_map(|(script, recommend)| (script.name == name).then_some(recommend))
+    }
+
+    /// Returns the first [`CategoryData`] named `name`, searching the `actions` tree recursively
+    pub fn find_category(&self, name: &str) -> Option<&CategoryData> {
+        self.actions.iter().find_map(|action| action.find_category(name))
+    }
+
+    /**
+    Iterates over every [`ScriptData`] in document order along with the names of its enclosing
+    [categories](CategoryData), outermost first
+
+    Each yielded path is a separate [`Vec`], cloned from a single path buffer used while walking the tree
+    */
+    pub fn iter_scripts(&self) -> impl Iterator<Item = (Vec<&str>, &ScriptData)> {
+        let mut scripts = Vec::new();
+        let mut path = Vec::new();
+        for action in &self.actions {
+            action.collect_scripts(&mut path, &mut scripts);
+        }
+        scripts.into_iter()
+    }
+
+    /// Returns every [`ScriptData`] whose name or documentation URLs contain `query`, ignoring case
+    pub fn search(&self, query: &str) -> Vec<&ScriptData> {
+        let query = query.to_lowercase();
+        self.iter_scripts()
+            .map(|(_, script)| script)
+            .filter(|script| {
+                script.name.to_lowercase().contains(&query)
+                    || script.doc_urls().iter().any(|url| url.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Returns the names of all [`ScriptData`] that can be reverted, i.e. parsed with `revert`
+    pub fn list_revertable(&self) -> Vec<&str> {
+        self.partition_revertable().0
+    }
+
+    /// Returns the names of all [`ScriptData`] that cannot be reverted, i.e. scripts with `code` but no
+    /// `revertCode` and scripts calling any function that cannot be reverted, the complement of
+    /// [`list_revertable`](CollectionData::list_revertable)
+    pub fn scripts_without_revert(&self) -> Vec<&str> {
+        self.partition_revertable().1
+    }
+
+    /// Partitions the names of all [`ScriptData`] into those that can be reverted and those that cannot
+    fn partition_revertable(&self) -> (Vec<&str>, Vec<&str>) {
+        let funcs = self
+            .functions
+            .iter()
+            .flatten()
+            .map(|fd| (fd.name.as_str(), fd))
+            .collect();
+
+        let (mut revertable, mut unrevertable) = (Vec::new(), Vec::new());
+        for (_, script) in self.iter_scripts() {
+            if script.is_revertable(&funcs) {
+                revertable.push(script.name.as_str());
+            } else {
+                unrevertable.push(script.name.as_str());
+            }
+        }
+        (revertable, unrevertable)
+    }
+
+    /// Returns a copy of [`CollectionData`] with only the [`ScriptData`] matching `predicate`, dropping categories
+    /// left empty
+    pub fn filter(&self, predicate: impl Fn(&ScriptData) -> bool) -> CollectionData {
+        CollectionData {
+            os: self.os,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            schema_version: self.schema_version.clone(),
+            scripting: self.scripting.clone(),
+            actions: self
+                .actions
+                .iter()
+                .filter_map(|action| action.filter(&predicate))
+                .collect(),
+            functions: self.functions.clone(),
+            imports: self.imports.clone(),
+        }
+    }
+
+    /// Applies `overrides` to [`CollectionData`], removing disabled [`ScriptData`] along with categories left empty
+    /// and setting the recommend levels of the others
+    pub fn apply_overrides(&mut self, overrides: &Overrides) {
+        *self = self.filter(|script| !overrides.disable.contains(&script.name));
+        for action in &mut self.actions {
+            action.for_each_script_mut(&mut |script| {
+                if let Some(level) = overrides.recommend.get(&script.name) {
+                    script.recommend = Some(*level);
+                }
+            });
+        }
+    }
+
+    /// Compares the [`ScriptData`] of [`CollectionData`] with those of `other` by name, e.g. to generate a changelog
+    pub fn diff(&self, other: &CollectionData) -> CollectionDiff {
+        let scripts = self
+            .iter_scripts()
+            .map(|(_, script)| (script.name.as_str(), script))
+            .collect::<HashMap<_, _>>();
+        let other_scripts = other
+            .iter_scripts()
+            .map(|(_, script)| (script.name.as_str(), script))
+            .collect::<HashMap<_, _>>();
+
+        let mut diff = CollectionDiff::default();
+        for (_, script) in self.iter_scripts() {
+            match other_scripts.get(script.name.as_str()) {
+                None => diff.removed.push(script.name.clone()),
+                Some(other) if other.code != script.code || other.revert_code != script.revert_code => {
+                    diff.changed.push(script.name.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        for (_, script) in other.iter_scripts() {
+            if !scripts.contains_key(script.name.as_str()) {
+                diff.added.push(script.name.clone());
+            }
+        }
+        diff
+    }
+
+    /**
+    Validates [`CollectionData`] for authoring mistakes not caught during deserialization
+
+    # Errors
+
+    Returns every [`ValidationError`] found
+    */
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let mut script_names = HashSet::new();
+        for (_, script) in self.iter_scripts() {
+            if !script_names.insert(script.name.as_str()) {
+                errors.push(ValidationError::DuplicateScript(script.name.clone()));
+            }
+            if script.code.is_some() == script.call.is_some() {
+                errors.push(ValidationError::CodeCallConflict(script.name.clone()));
+            }
+            // Entries with whitespace are markdown documentation rather than URLs
+            for url in script.doc_urls() {
+                if !url.contains(char::is_whitespace) && Url::parse(url).is_err() {
+                    errors.push(ValidationError::InvalidDocUrl {
+                        script: script.name.clone(),
+                        url: url.to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut categories = self.actions.iter().collect::<Vec<_>>();
+        let mut category_names = HashSet::new();
+        while let Some(category) = categories.pop() {
+            if !category_names.insert(category.category.as_str()) {
+                errors.push(ValidationError::DuplicateCategory(category.category.clone()));
+            }
+            categories.extend(category.children.iter().filter_map(|child| match child {
+                CategoryOrScriptData::CategoryData(data) => Some(data),
+                CategoryOrScriptData::ScriptData(_) => None,
+            }));
+        }
+
+        let funcs = self.functions.as_deref().unwrap_or_default();
+        let mut function_names = HashSet::new();
+        for fd in funcs {
+            if !function_names.insert(fd.name.as_str()) {
+                errors.push(ValidationError::DuplicateFunction(fd.name.clone()));
+            }
+            if fd.code.is_some() == fd.call.is_some() {
+                errors.push(ValidationError::CodeCallConflict(fd.name.clone()));
+            }
+
+            let used = fd.used_parameters();
+            let declared = fd
+                .parameters
+                .iter()
+                .flatten()
+                .map(|pdd| pdd.name.as_str())
+                .collect::<Vec<_>>();
+            for name in &declared {
+                if !used.contains(name) {
+                    errors.push(ValidationError::UnusedParameter(fd.name.clone(), name.to_string()));
+                }
+            }
+            for name in used {
+                if !declared.contains(&name) {
+                    errors.push(ValidationError::UndeclaredParameter(fd.name.clone(), name.to_string()));
+                }
+            }
+        }
+
+        let calls = self
+            .iter_scripts()
+            .filter_map(|(_, script)| script.call.as_ref())
+            .chain(funcs.iter().filter_map(|fd| fd.call.as_ref()))
+            .flat_map(FunctionCallsData::as_slice);
+        for fcd in calls {
+            let Some(fd) = funcs.iter().find(|fd| fd.name == fcd.function) else {
+                errors.push(ValidationError::UnknownFunction(fcd.function.clone()));
+                continue;
+            };
+            for pdd in fd.parameters.iter().flatten() {
+                let provided = fcd.parameters.as_ref().is_some_and(|p| p.get(&pdd.name).is_some());
+                if !pdd.optional && pdd.default.is_none() && !provided {
+                    errors.push(ValidationError::MissingCallParameter {
+                        call: fcd.function.clone(),
+                        param: pdd.name.clone(),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /**
+    Checks that the `schemaVersion` of [`CollectionData`] is compatible with the `supported` version, usually
+    [`SCHEMA_VERSION`], following semver: versions are compatible if they share the major version, or the minor
+    version for `0.x` versions
+
+    Collections without a `schemaVersion` are assumed compatible. A collection written for a newer minor version
+    is accepted with a warning, as it may use features that are ignored.
+
+    # Errors
+
+    Returns [`SchemaError`] if:
+    - either version is not of the form `MAJOR.MINOR.PATCH`, as [`SchemaError::InvalidVersion`] OR
+    - the versions are not compatible, as [`SchemaError::Incompatible`]
+    */
+    pub fn check_schema_version(&self, supported: &str) -> Result<(), SchemaError> {
+        let Some(found) = &self.schema_version else {
+            return Ok(());
+        };
+        let (major, minor, patch) = parse_version(found)?;
+        let (supported_major, supported_minor, supported_patch) = parse_version(supported)?;
+
+        if major != supported_major || (major == 0 && minor != supported_minor) {
+            return Err(SchemaError::Incompatible {
+                found: found.clone(),
+                supported: supported.to_string(),
+            });
+        }
+        if (minor, patch) > (supported_minor, supported_patch) {
+            warn!("collection schema version {found} is newer than supported version {supported}");
+        }
+        Ok(())
+    }
+
+    /// Checks whether any [`ScriptData`] is recommended exactly at `level`
+    pub fn has_recommend(&self, level: Recommend) -> bool {
+        self.recommends()
+            .into_iter()
+            .any(|(_, recommend)| recommend == Some(level))
+    }
+
+    /// Returns every [`ScriptData`] in document order along with its recommend level, inherited from its
+    /// [categories](CategoryData) unless it defines its own
+    fn recommends(&self) -> Vec<(&ScriptData, Option<Recommend>)> {
+        let mut scripts = Vec::new();
+        for action in &self.actions {
+            action.collect_recommends(None, &mut scripts);
+        }
+        scripts
+    }
+
+    /**
+    Parses [`CollectionData`] into String
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn parse(
+        &self,
+        names: Option<&Vec<&str>>,
+        revert: bool,
+        recommend: Option<Recommend>,
+    ) -> Result<String, ParseError> {
+        self.parse_with(&ParseOptions {
+            names: names.map(|ns| ns.iter().map(ToString::to_string).collect()),
+            revert,
+            recommend,
+            ..Default::default()
+        })
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn parse_with(&self, options: &ParseOptions) -> Result<String, ParseError> {
+        self.parse_stats(options).map(|(script, _)| script)
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`, along with [`ParseStats`] of the output
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn parse_stats(&self, options: &ParseOptions) -> Result<(String, ParseStats), ParseError> {
+        self.render_all(options, None, None)
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`, omitting every script whose code contains any
+    of the substrings in `denylist`, e.g. `rm -rf /`, and pushing its name to `omitted`. The name of the script is
+    not matched.
+
+    This is a coarse heuristic matching plain substrings, it neither understands the code nor catches commands
+    that are quoted, aliased or built at run time differently than listed, and may omit harmless scripts that
+    merely mention a listed substring
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn parse_excluding(
+        &self,
+        options: &ParseOptions,
+        denylist: &[&str],
+        omitted: &mut Vec<String>,
+    ) -> Result<String, ParseError> {
+        self.render_all(options, None, Some((denylist, omitted)))
+            .map(|(script, _)| script)
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`, skipping scripts that are not parsable
+    instead of failing on the first one, along with every [`ParseError`] encountered
+
+    Nothing is rendered if the functions of the collection are not unique, as [`ParseError::DuplicateFunction`]
+    */
+    pub fn parse_collect_errors(&self, options: &ParseOptions) -> (String, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        match self.render_all(options, Some(&mut errors), None) {
+            Ok((script, _)) => (script, errors),
+            Err(e) => (String::new(), vec![e]),
+        }
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`, along with [`ParseStats`] of the output
+
+    Errors of scripts are pushed to `errors` and the scripts skipped if given, instead of being returned. Scripts
+    with code containing any substring of the `denylist` are skipped and their names pushed along with it if given.
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    fn render_all(
+        &self,
+        options: &ParseOptions,
+        errors: Option<&mut Vec<ParseError>>,
+        denylist: Option<(&[&str], &mut Vec<String>)>,
+    ) -> Result<(String, ParseStats), ParseError> {
+        let mut script = String::new();
+        let script_count = self.render_into(options, errors, denylist, &mut |piece| {
+            script.push_str(piece);
+            Ok(())
+        })?;
+
+        let stats = ParseStats {
+            scripts: script_count,
+            lines: script.lines().count(),
+            bytes: script.len(),
+        };
+        Ok((script, stats))
+    }
+
+    /**
+    Parses [`CollectionData`] into String as configured by `options`, writing it to `writer` piece by piece
+    instead of building it in memory
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - the object is not parsable OR
+    - writing fails, as [`ParseError::IOError`]
+    */
+    pub fn write_parsed(&self, mut writer: impl Write, options: &ParseOptions) -> Result<(), ParseError> {
+        self.render_into(options, None, None, &mut |piece| writer.write_all(piece.as_bytes()))?;
+        writer.flush().map_err(ParseError::IOError)
+    }
+
+    /**
+    Parses [`CollectionData`] as configured by `options`, passing each piece of the output to `out` as soon as
+    it is rendered, returning the number of scripts included
+
+    Errors of scripts are pushed to `errors` and the scripts skipped if given, instead of being returned. Scripts
+    with code containing any substring of the `denylist` are skipped and their names pushed along with it if given.
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - the object is not parsable OR
+    - `out` fails, as [`ParseError::IOError`]
+    */
+    fn render_into(
+        &self,
+        options: &ParseOptions,
+        mut errors: Option<&mut Vec<ParseError>>,
+        mut denylist: Option<(&[&str], &mut Vec<String>)>,
+        out: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> Result<usize, ParseError> {
+        let funcs = self.function_map()?;
+        let indices = self.script_indices();
+        let mut script_count = 0;
+        let separator = options.separator.as_deref().unwrap_or("\n\n\n");
+        let mut out = |piece: &str| out(piece).map_err(ParseError::IOError);
+
+        if options.bom && matches!(self.os, OS::Windows) {
+            out(BOM)?;
+        }
+
+        if options.include_wrapper {
+            out(&parse_start_end(
+                &self.scripting.start_code,
+                self.os,
+                options.date.as_deref(),
+            ))?;
+            out(separator)?;
+        }
+
+        let mut first_block = true;
+        if options.sudo && matches!(self.os, OS::Linux) {
+            out(SUDO_CHECK)?;
+            first_block = false;
+        }
+
+        // Consecutive scripts of the same top-level category form a block
+        let mut block_action = None;
+        for (action, script) in self.select(options)? {
+            let index = indices.get(&ptr::from_ref(script)).copied().unwrap_or_default();
+            let code = match (self.render_code(script, index, &funcs, options), errors.as_deref_mut()) {
+                (Ok(code), _) => code,
+                (Err(e), Some(errors)) => {
+                    errors.push(e);
+                    continue;
+                }
+                (Err(e), None) => return Err(e),
+            };
+
+            // Match the code only, as the banner & echo call name the script
+            if let Some((denied, omitted)) = denylist.as_mut() {
+                if denied.iter().any(|pattern| code.contains(pattern)) {
+                    omitted.push(script.name.clone());
+                    continue;
+                }
+            }
+            let code = self.beautify_code(script, code, options);
+
+            if block_action == Some(action) {
+                out(separator)?;
+            } else {
+                if !first_block {
+                    out(separator)?;
+                }
+                first_block = false;
+                block_action = Some(action);
+                if options.category_headers && !options.raw {
+                    out(&category_header(&self.actions[action].category, &*self.renderer()))?;
+                    out(separator)?;
+                }
+            }
+
+            out(&code)?;
+            script_count += 1;
+        }
+
+        debug!("selected {script_count} scripts");
+
+        if options.include_wrapper {
+            out(separator)?;
+            out(&parse_start_end(
+                &self.scripting.end_code,
+                self.os,
+                options.date.as_deref(),
+            ))?;
+        }
+
+        Ok(script_count)
+    }
+
+    /**
+    Parses the [`ScriptData`] named `name` into String, without the start and end code
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - the script is not found, as [`ParseError::UnknownNames`] OR
+    - the script is not parsable
+    */
+    pub fn parse_one(&self, name: &str, revert: bool) -> Result<String, ParseError> {
+        let script = self
+            .find_script(name)
+            .ok_or_else(|| ParseError::UnknownNames(vec![name.to_string()]))?;
+        self.render(
+            script,
+            self.script_index(script),
+            &self.function_map()?,
+            &ParseOptions {
+                revert,
+                ..Default::default()
+            },
+        )
+    }
+
+    /**
+    Describes the [`ScriptData`] named `name` with its parsed code, as by [`parse_one`](CollectionData::parse_one),
+    and documentation URLs
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - the script is not found, as [`ParseError::UnknownNames`] OR
+    - the script is not parsable
+    */
+    pub fn describe_script(&self, name: &str) -> Result<ScriptDescription, ParseError> {
+        let script = self
+            .find_script(name)
+            .ok_or_else(|| ParseError::UnknownNames(vec![name.to_string()]))?;
+        let funcs = self.function_map()?;
+        let index = self.script_index(script);
+
+        Ok(ScriptDescription {
+            name: script.name.clone(),
+            code: self.render(script, index, &funcs, &ParseOptions::default())?,
+            revert_code: if script.is_revertable(&funcs) {
+                Some(self.render(
+                    script,
+                    index,
+                    &funcs,
+                    &ParseOptions {
+                        revert: true,
+                        ..Default::default()
+                    },
+                )?)
+            } else {
+                None
+            },
+            recommend: self.recommend_of(name).flatten(),
+            doc_urls: script
+                .doc_urls()
+                .into_iter()
+                .map(|url| url.trim().to_string())
+                .collect(),
+        })
+    }
+
+    /**
+    Parses each [`ScriptData`] selected by `options` separately, without the start and end code, into its name and
+    code
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn parse_segments(&self, options: &ParseOptions) -> Result<Vec<(String, String)>, ParseError> {
+        let funcs = self.function_map()?;
+        let indices = self.script_indices();
+        self.preview(options)?
+            .into_iter()
+            .map(|script| {
+                let index = indices.get(&ptr::from_ref(script)).copied().unwrap_or_default();
+                Ok((script.name.clone(), self.render(script, index, &funcs, options)?))
+            })
+            .collect()
+    }
+
+    /**
+    Parses each [`ScriptData`] selected by `options` into a function named `tweak_<name>`, with `<name>` being the
+    lowercase name of the script where each run of characters other than letters and digits is replaced by `_`,
+    e.g. `tweak_clear_bash_history`
+
+    Functions are defined as rendered by [`ScriptRenderer::function_library`], i.e. as `tweak_<name>() { ... }` for
+    shell scripts, `function tweak_<name> { ... }` for PowerShell and as `:tweak_<name>` labels to be called with
+    `call :tweak_<name>` for batch files
+
+    # Errors
+
+    Returns [`ParseError`] if the object is not parsable
+    */
+    pub fn to_function_library(&self, options: &ParseOptions) -> Result<String, ParseError> {
+        let functions = self
+            .parse_segments(options)?
+            .into_iter()
+            .map(|(name, code)| (format!("tweak_{}", function_name(&name)), code))
+            .collect::<Vec<_>>();
+        Ok(self.renderer().function_library(&functions))
+    }
+
+    /**
+    Returns [`TemplateStats`] of the functions of the collection
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - a function name is not unique, as [`ParseError::DuplicateFunction`] OR
+    - a called function is not found, as [`ParseError::Function`] OR
+    - functions call each other, as [`ParseError::CallCycle`]
+    */
+    pub fn template_stats(&self) -> Result<TemplateStats, ParseError> {
+        let funcs = self.function_map()?;
+        let mut stats = TemplateStats::default();
+        let mut depths = HashMap::new();
+        for fd in self.functions.iter().flatten() {
+            let parameters = fd.parameters.as_ref().map_or(0, Vec::len);
+            if parameters > 0 {
+                stats.parameterized_functions += 1;
+                stats.parameters += parameters;
+            }
+            stats.max_call_depth = stats
+                .max_call_depth
+                .max(call_depth(fd, &funcs, &mut Vec::new(), &mut depths)?);
+        }
+        Ok(stats)
+    }
+
+    /**
+    Returns the [`ScriptData`] selected by `options` along with the scripts they require, in the order they are
+    parsed
+
+    # Errors
+
+    Returns [`ParseError`] if:
+    - a required script is not found, as [`ParseError::UnknownNames`] OR
+    - scripts require each other, as [`ParseError::DependencyCycle`]
+    */
+    pub fn preview(&self, options: &ParseOptions) -> Result<Vec<&ScriptData>, ParseError> {
+        Ok(self.select(options)?.into_iter().map(|(_, script)| script).collect())
+    }
+
+    /**
+    Returns the [`ScriptData`] selected by `options` along with the index of their top-level category, sorted
+    by [`ParseOptions::sort`] and then by descending `priority`, with the scripts they require included before them
+    (after them when reverting)
+
+    # Errors
+
+    Returns [`ParseError`] if a required script is not found or scripts require each other
+    */
+    fn select(&self, options: &ParseOptions) -> Result<Vec<(usize, &ScriptData)>, ParseError> {
+        let mut selected = Vec::new();
+        for (i, action) in self.actions.iter().enumerate() {
+            let mut scripts = Vec::new();
+            action.select(
+                options.names.as_deref(),
+                options.recommend,
+                options.include_unrecommended,
+                None,
+                &mut scripts,
+            );
+            selected.extend(scripts.into_iter().map(|script| (i, script)));
+        }
+        match options.sort {
+            SortOrder::Document => {}
+            SortOrder::Alphabetical => selected.sort_by_cached_key(|(_, script)| script.name.to_lowercase()),
+            SortOrder::Recommend => {
+                let levels = self
+                    .recommends()
+                    .into_iter()
+                    .map(|(script, recommend)| (script.name.as_str(), recommend))
+                    .collect::<HashMap<_, _>>();
+                selected.sort_by_key(
+                    |(_, script)| match levels.get(script.name.as_str()).copied().flatten() {
+                        Some(Recommend::Strict) => 0,
+                        Some(Recommend::Standard) => 1,
+                        None => 2,
+                    },
+                );
+            }
+        }
+        selected.sort_by_key(|(_, script)| Reverse(script.priority));
+
+        if selected.iter().all(|(_, script)| script.requires.is_none()) {
+            return Ok(selected);
+        }
+
+        let mut all = HashMap::new();
+        for (i, action) in self.actions.iter().enumerate() {
+            let mut scripts = Vec::new();
+            action.collect_recommends(None, &mut scripts);
+            for (script, _) in scripts {
+                all.entry(script.name.as_str()).or_insert((i, script));
+            }
+        }
+
+        let mut ordered = Vec::new();
+        let mut done = HashSet::new();
+        for entry in selected {
+            order_requires(entry, &all, &mut Vec::new(), &mut done, &mut ordered)?;
+        }
+        if !options.revert {
+            return Ok(ordered);
+        }
+
+        // Undo scripts before the scripts they require
+        let mut dependents = HashMap::<_, Vec<_>>::new();
+        for entry in &ordered {
+            for name in entry.1.requires.iter().flatten() {
+                dependents.entry(name.as_str()).or_default().push(*entry);
+            }
+        }
+        let mut reverted = Vec::new();
+        let mut done = HashSet::new();
+        for entry in ordered {
+            order_dependents(entry, &dependents, &mut done, &mut reverted);
+        }
+        Ok(reverted)
+    }
+
     /**
-    Reads [`CollectionData`] from file at `path`
+    Runs each [`ScriptData`] selected by `options` as a separate script wrapped in the start and end code,
+    calling `on_progress` with the name and exit status of each script once it exits
+
+    All scripts are parsed before any of them is run, and run with
+    [`ScriptingDefinitionData::default_interpreter`]
 
     # Errors
 
-    Returns [`CollectionError`] if:
-    - file cannot be opened OR
-    - contents cannot be deserialized into [`CollectionData`]
+    Returns [`RunError`] if:
+    - a script is not parsable OR
+    - a script cannot be run, refer to [`run_script_with`](crate::run_script_with)
     */
-    pub fn from_file(path: impl AsRef<Path>) -> Result<CollectionData, CollectionError> {
-        Ok(serde_yaml::from_reader::<File, CollectionData>(File::open(path)?)?)
+    pub fn run_each(
+        &self,
+        options: &ParseOptions,
+        mut on_progress: impl FnMut(&str, ExitStatus),
+    ) -> Result<(), RunError> {
+        for (name, code) in self.parse_each(options)? {
+            let status = run_script_with(
+                &code,
+                Some(self.effective_file_extension().to_string()),
+                self.scripting.default_interpreter(),
+            )?;
+            on_progress(&name, status);
+        }
+
+        Ok(())
     }
 
     /**
-    Fetches [`CollectionData`] from `url`
+    Parses each [`ScriptData`] selected by `options` separately into its name and code wrapped in the start and
+    end code, i.e. a standalone script as run by [`CollectionData::run_each`], prefixed with a byte order mark if
+    [`ParseOptions::bom`]
 
     # Errors
 
-    Returns [`CollectionError`] if:
-    - `url` cannot be fetched OR
-    - contents cannot be deserialized into [`CollectionData`]
+    Returns [`ParseError`] if the object is not parsable
     */
-    pub fn from_url(url: impl IntoUrl) -> Result<CollectionData, CollectionError> {
-        Ok(serde_yaml::from_slice::<CollectionData>(&get(url)?.bytes()?)?)
+    pub fn parse_each(&self, options: &ParseOptions) -> Result<Vec<(String, String)>, ParseError> {
+        let bom = if options.bom && matches!(self.os, OS::Windows) {
+            BOM
+        } else {
+            ""
+        };
+        let start = format!(
+            "{bom}{}",
+            parse_start_end(&self.scripting.start_code, self.os, options.date.as_deref())
+        );
+        let end = parse_start_end(&self.scripting.end_code, self.os, options.date.as_deref());
+
+        Ok(self
+            .parse_segments(options)?
+            .into_iter()
+            .map(|(name, code)| (name, format!("{start}\n\n\n{code}\n\n\n{end}")))
+            .collect())
+    }
+
+    /// Parses `script` as configured by `options`, refer to [`ParseOptions::raw`] and [`ParseOptions::strip_comments`].
+    /// `index` is the position of `script` in [`CollectionData::iter_scripts`], labelling its check code
+    fn render(
+        &self,
+        script: &ScriptData,
+        index: usize,
+        funcs: &Functions,
+        options: &ParseOptions,
+    ) -> Result<String, ParseError> {
+        let code = self.render_code(script, index, funcs, options)?;
+        Ok(self.beautify_code(script, code, options))
+    }
+
+    /// Parses the code of `script` as configured by `options`, before it is beautified by
+    /// [`CollectionData::beautify_code`]
+    fn render_code(
+        &self,
+        script: &ScriptData,
+        index: usize,
+        funcs: &Functions,
+        options: &ParseOptions,
+    ) -> Result<String, ParseError> {
+        if options.require_docs && script.docs.is_none() {
+            return Err(ParseError::MissingDocs(script.name.clone()));
+        }
+
+        let renderer = self.renderer();
+        let mut code = script.code(funcs, self.os, options.revert)?;
+        if options.strip_comments {
+            code = renderer.strip_comments(&code);
+        }
+        if let Some(check_code) = script.check_code.as_ref().filter(|_| !options.revert) {
+            code = renderer.skip_if_applied(&code, check_code, &format!("{}_{index}", function_name(&script.name)));
+        }
+        Ok(code)
+    }
+
+    /// Wraps `code` of `script` in comments and an echo call, unless [`ParseOptions::raw`]
+    fn beautify_code(&self, script: &ScriptData, code: String, options: &ParseOptions) -> String {
+        if options.raw {
+            code
+        } else {
+            beautify(
+                &code,
+                &script.name,
+                &*self.renderer(),
+                options.revert,
+                self.scripting.echo_command.as_deref(),
+            )
+        }
+    }
+
+    /// Maps each [`ScriptData`] to its position in [`CollectionData::iter_scripts`]
+    fn script_indices(&self) -> HashMap<*const ScriptData, usize> {
+        self.iter_scripts()
+            .enumerate()
+            .map(|(i, (_, script))| (ptr::from_ref(script), i))
+            .collect()
+    }
+
+    /// Returns the position of `script` in [`CollectionData::iter_scripts`]
+    fn script_index(&self, script: &ScriptData) -> usize {
+        self.iter_scripts()
+            .position(|(_, s)| ptr::eq(s, script))
+            .unwrap_or_default()
+    }
+
+    /// Returns the [`ScriptRenderer`] registered for the scripting language, refer to [`renderer::get_renderer`]
+    fn renderer(&self) -> Arc<dyn ScriptRenderer> {
+        renderer::get_renderer(&self.scripting.language, self.os)
     }
 
     /**
-    Parses [`CollectionData`] into String
+    Maps the names of [`FunctionData`] to themselves
 
     # Errors
 
-    Returns [`ParseError`] if the object is not parsable
+    Returns [`ParseError::DuplicateFunction`] if a function name is not unique
     */
-    pub fn parse(
-        &self,
-        names: Option<&Vec<&str>>,
-        revert: bool,
-        recommend: Option<Recommend>,
-    ) -> Result<String, ParseError> {
-        Ok(format!(
-            "{}\n\n\n{}\n\n\n{}",
-            parse_start_end(&self.scripting.start_code),
-            self.actions
-                .iter()
-                .map(|action| action.parse(names, &self.functions, self.os, revert, recommend))
-                .collect::<Result<Vec<_>, _>>()?
-                .into_iter()
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<_>>()
-                .join("\n\n\n"),
-            parse_start_end(&self.scripting.end_code),
-        ))
+    fn function_map(&self) -> Result<Functions<'_>, ParseError> {
+        let mut funcs = HashMap::new();
+        for fd in self.functions.iter().flatten() {
+            if funcs.insert(fd.name.as_str(), fd).is_some() {
+                return Err(ParseError::DuplicateFunction(fd.name.clone()));
+            }
+        }
+        Ok(funcs)
+    }
+}
+
+/// Options for [`CollectionData::parse_with`]
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// Names of scripts or categories to include, includes every script if `None`
+    pub names: Option<Vec<String>>,
+    /// Use `revertCode` instead of `code`
+    pub revert: bool,
+    /// Include only scripts recommended at this level, includes every script if `None`
+    pub recommend: Option<Recommend>,
+    /// Prepend a comment header with the name of each top-level [category](CategoryData)
+    pub category_headers: bool,
+    /// Also include scripts without any `recommend` level when `recommend` is set
+    pub include_unrecommended: bool,
+    /// Separator between the start code, category headers, scripts and end code, `"\n\n\n"` if `None`
+    pub separator: Option<String>,
+    /// Wrap the scripts in the start and end code of [`ScriptingDefinitionData`], `true` by default
+    pub include_wrapper: bool,
+    /// Emit only the code of each script, without its comment banner and echo command or any category header
+    pub raw: bool,
+    /**
+    Insert the following check before the scripts of a [`OS::Linux`] collection, re-running the script with `sudo`
+    unless it is already run as root. Ignored for other [`OS`]es.
+
+    ```sh
+    if [ "$(id -u)" -ne 0 ]; then
+      exec sudo "$0" "$@"
+    fi
+    ```
+    */
+    pub sudo: bool,
+    /// Remove comment lines from the code of each script as done by [`ScriptRenderer::strip_comments`], i.e. lines
+    /// starting with a `#`, `::` or `REM` comment (except shebangs), and `<# .. #>` blocks for PowerShell
+    pub strip_comments: bool,
+    /// Fail with [`ParseError::MissingDocs`] if any selected script has no `docs`
+    pub require_docs: bool,
+    /**
+    Prepend a UTF-8 byte order mark (`EF BB BF`) to the script of a [`OS::Windows`] collection, as expected by
+    some Windows tools reading `.bat` & `.ps1` files, or to each script of
+    [`parse_each`](CollectionData::parse_each). Ignored for other [`OS`]es and by
+    [`parse_segments`](CollectionData::parse_segments).
+    */
+    pub bom: bool,
+    /// Order of the scripts, scripts with a higher `priority` are still parsed first, refer to [`ScriptData::priority`]
+    pub sort: SortOrder,
+    /// Text substituted for `$date` in the start and end code instead of the current time, e.g. for reproducible
+    /// output
+    pub date: Option<String>,
+}
+
+/// Order of the scripts parsed by [`CollectionData::parse_with`], refer to [`ParseOptions::sort`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Order of the scripts in the collection
+    #[default]
+    Document,
+    /// Alphabetical order of the script names, ignoring case
+    Alphabetical,
+    /// Scripts recommended as `strict` first, then `standard` and then those not recommended, each in document
+    /// order
+    Recommend,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            names: None,
+            revert: false,
+            recommend: None,
+            category_headers: false,
+            include_unrecommended: false,
+            separator: None,
+            include_wrapper: true,
+            raw: false,
+            sudo: false,
+            strip_comments: false,
+            require_docs: false,
+            bom: false,
+            sort: SortOrder::Document,
+            date: None,
+        }
     }
 }
 
+/// Differences between the scripts of two collections, refer to [`CollectionData::diff`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollectionDiff {
+    /// Names of [`ScriptData`] only in the other collection
+    pub added: Vec<String>,
+    /// Names of [`ScriptData`] missing from the other collection
+    pub removed: Vec<String>,
+    /// Names of [`ScriptData`] whose `code` or `revertCode` differs in the other collection
+    pub changed: Vec<String>,
+}
+
+/// Policy layered on top of a [`CollectionData`], refer to [`CollectionData::apply_overrides`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Overrides {
+    /// Names of [`ScriptData`] to remove
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Recommend levels to set, by name of [`ScriptData`]
+    #[serde(default)]
+    pub recommend: HashMap<String, Recommend>,
+}
+
+/// Details of a [`ScriptData`], refer to [`CollectionData::describe_script`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptDescription {
+    /// Name of the script
+    pub name: String,
+    /// Parsed code
+    pub code: String,
+    /// Parsed revert code, if the script is revertable
+    pub revert_code: Option<String>,
+    /// Recommend level, if recommended
+    pub recommend: Option<Recommend>,
+    /// Trimmed documentation URLs
+    pub doc_urls: Vec<String>,
+}
+
+/// Metrics of the output of [`CollectionData::parse_stats`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Number of [`ScriptData`] included
+    pub scripts: usize,
+    /// Number of lines
+    pub lines: usize,
+    /// Length in bytes
+    pub bytes: usize,
+}
+
+/// Complexity of the functions of a collection, refer to [`CollectionData::template_stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TemplateStats {
+    /// Number of [`FunctionData`] with at least one parameter
+    pub parameterized_functions: usize,
+    /// Total number of [`ParameterDefinitionData`] across all functions
+    pub parameters: usize,
+    /// Depth of the deepest chain of functions calling each other, `1` if no function calls another and `0` if there
+    /// are no functions
+    pub max_call_depth: usize,
+}
+
 /**
 ### `Category`
 
 - Category has a parent that has tree-like structure where it can have subcategories or subscripts.
 - It's a logical grouping of different scripts and other categories.
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CategoryData {
     /// - ❗ Category must consist of at least one subcategory or script.
     /// - Children can be combination of scripts and subcategories.
@@ -131,44 +1686,127 @@ pub struct CategoryData {
     /// - Single documentation URL or list of URLs for those who wants to learn more about the script
     /// - E.g. `https://docs.microsoft.com/en-us/windows-server/`
     pub docs: Option<DocumentationUrlsData>,
+    /**
+    - Recommend level of the scripts in the category and its subcategories, refer to [`ScriptData::recommend`]
+    - ❗ A `recommend` defined by a script or a nested category takes precedence
+    */
+    pub recommend: Option<Recommend>,
 }
 
 impl CategoryData {
-    /**
-    Parses [`CategoryData`] into String
+    /// Returns the first [`ScriptData`] named `name` in this category or its subcategories
+    fn find_script(&self, name: &str) -> Option<&ScriptData> {
+        self.children.iter().find_map(|child| match child {
+            CategoryOrScriptData::CategoryData(data) => data.find_script(name),
+            CategoryOrScriptData::ScriptData(data) => (data.name == name).then_some(data),
+        })
+    }
 
-    # Errors
+    /// Returns this category or the first subcategory named `name`
+    fn find_category(&self, name: &str) -> Option<&CategoryData> {
+        if self.category == name {
+            return Some(self);
+        }
 
-    Returns [`ParseError`] if the object is not parsable
-    */
-    fn parse(
-        &self,
-        names: Option<&Vec<&str>>,
-        funcs: &Option<Vec<FunctionData>>,
-        os: OS,
-        revert: bool,
+        self.children.iter().find_map(|child| match child {
+            CategoryOrScriptData::CategoryData(data) => data.find_category(name),
+            CategoryOrScriptData::ScriptData(_) => None,
+        })
+    }
+
+    /// Collects every script in this category and its subcategories along with its category path
+    fn collect_scripts<'a>(&'a self, path: &mut Vec<&'a str>, scripts: &mut Vec<(Vec<&'a str>, &'a ScriptData)>) {
+        path.push(&self.category);
+        for child in &self.children {
+            match child {
+                CategoryOrScriptData::CategoryData(data) => data.collect_scripts(path, scripts),
+                CategoryOrScriptData::ScriptData(data) => scripts.push((path.clone(), data)),
+            }
+        }
+        path.pop();
+    }
+
+    /// Calls `f` on every script in this category and its subcategories
+    fn for_each_script_mut(&mut self, f: &mut impl FnMut(&mut ScriptData)) {
+        for child in &mut self.children {
+            match child {
+                CategoryOrScriptData::CategoryData(data) => data.for_each_script_mut(f),
+                CategoryOrScriptData::ScriptData(data) => f(data),
+            }
+        }
+    }
+
+    /// Clones this category with only the scripts matching `predicate`, `None` if no script matches
+    fn filter(&self, predicate: &impl Fn(&ScriptData) -> bool) -> Option<CategoryData> {
+        let children = self
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                CategoryOrScriptData::CategoryData(data) => {
+                    data.filter(predicate).map(CategoryOrScriptData::CategoryData)
+                }
+                CategoryOrScriptData::ScriptData(data) => predicate(data).then(|| child.clone()),
+            })
+            .collect::<Vec<_>>();
+
+        (!children.is_empty()).then(|| CategoryData {
+            children,
+            category: self.category.clone(),
+            docs: self.docs.clone(),
+            recommend: self.recommend,
+        })
+    }
+
+    /// Collects every script in this category and its subcategories along with its effective recommend level,
+    /// inheriting `inherited` unless overridden
+    fn collect_recommends<'a>(
+        &'a self,
+        inherited: Option<Recommend>,
+        scripts: &mut Vec<(&'a ScriptData, Option<Recommend>)>,
+    ) {
+        let inherited = self.recommend.or(inherited);
+        for child in &self.children {
+            match child {
+                CategoryOrScriptData::CategoryData(data) => data.collect_recommends(inherited, scripts),
+                CategoryOrScriptData::ScriptData(data) => scripts.push((data, data.recommend.or(inherited))),
+            }
+        }
+    }
+
+    /// Collects scripts in this category and its subcategories selected by `names` and `recommend`, scripts
+    /// inheriting `inherited` as their recommend level unless overridden
+    fn select<'a>(
+        &'a self,
+        names: Option<&[String]>,
         recommend: Option<Recommend>,
-    ) -> Result<String, ParseError> {
-        let (names, recommend) = if names.map_or(false, |ns| ns.contains(&self.category.as_str())) {
+        include_unrecommended: bool,
+        inherited: Option<Recommend>,
+        scripts: &mut Vec<&'a ScriptData>,
+    ) {
+        let (names, recommend) = if names.is_some_and(|ns| ns.contains(&self.category)) {
             (None, None)
         } else {
             (names, recommend)
         };
+        let inherited = self.recommend.or(inherited);
 
-        Ok(self
-            .children
-            .iter()
-            .map(|child| child.parse(names, funcs, os, revert, recommend))
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n\n\n"))
+        for child in &self.children {
+            match child {
+                CategoryOrScriptData::CategoryData(data) => {
+                    data.select(names, recommend, include_unrecommended, inherited, scripts)
+                }
+                CategoryOrScriptData::ScriptData(data) => {
+                    if data.is_selected(names, recommend, include_unrecommended, inherited) {
+                        scripts.push(data);
+                    }
+                }
+            }
+        }
     }
 }
 
 /// Enum to hold possible values
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CategoryOrScriptData {
     /// Refer to [Collection](CategoryData)
@@ -177,32 +1815,9 @@ pub enum CategoryOrScriptData {
     ScriptData(ScriptData),
 }
 
-impl CategoryOrScriptData {
-    /**
-    Parses [`CategoryOrScriptData`] into String
-
-    # Errors
-
-    Returns [`ParseError`] if the object is not parsable
-    */
-    fn parse(
-        &self,
-        names: Option<&Vec<&str>>,
-        funcs: &Option<Vec<FunctionData>>,
-        os: OS,
-        revert: bool,
-        recommend: Option<Recommend>,
-    ) -> Result<String, ParseError> {
-        match self {
-            CategoryOrScriptData::CategoryData(data) => data.parse(names, funcs, os, revert, recommend),
-            CategoryOrScriptData::ScriptData(data) => data.parse(names, funcs, os, revert, recommend),
-        }
-    }
-}
-
 /// - Single documentation URL or list of URLs for those who wants to learn more about the script
 /// - E.g. `https://docs.microsoft.com/en-us/windows-server/`
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DocumentationUrlsData {
     /// Multiple URLs
@@ -217,12 +1832,12 @@ pub enum DocumentationUrlsData {
 - Defines a parameter that function requires optionally or mandatory.
 - Its arguments are provided by a [Script](ScriptData) through a [FunctionCall](FunctionCallData).
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ParameterDefinitionData {
     /**
     - Name of the parameters that the function has.
     - Parameter names must be defined to be used in
-    [expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions).
+      [expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions).
     - ❗ Parameter names must be unique and include alphanumeric characters only.
     */
     pub name: String,
@@ -233,10 +1848,31 @@ pub struct ParameterDefinitionData {
     - 💡 Set it to `true` if a parameter is used conditionally;
       - Or else set it to `false` for verbosity or do not define it as default value is `false` anyway.
     - 💡 Can be used in conjunction with
-    [`with` expression](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#with).
+      [`with` expression](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#with).
     */
     #[serde(default)]
     pub optional: bool,
+    /**
+    - Value used if the caller [Script](ScriptData) does not provide any value for the parameter.
+    - 💡 Allows omitting arguments of non-optional parameters that are mostly called with the same value.
+    - Can be a string, number or boolean, just like the arguments of a [FunctionCall](FunctionCallData).
+    */
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub default: Option<String>,
+}
+
+/// Deserializes an optional string, number or boolean as a string
+fn deserialize_scalar<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(Value::Number(n)) => Ok(Some(n.to_string())),
+        Some(Value::Bool(b)) => Ok(Some(b.to_string())),
+        Some(_) => Err(serde::de::Error::custom("expected a string, number or boolean")),
+    }
 }
 
 /**
@@ -250,9 +1886,9 @@ pub struct ParameterDefinitionData {
   2. Caller function: a function that calls other functions.
      - Must define `call` property but not `code` or `revertCode`.
 - 👀 Read more on [Templating](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md) for function expressions
-    and [example usages](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution).
+  and [example usages](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution).
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FunctionData {
     /**
     - Name of the function that scripts will use.
@@ -264,7 +1900,7 @@ pub struct FunctionData {
     /**
     - Batch file commands that will be executed
     - 💡 [Expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-        can be used in its value
+      can be used in its value
     - 💡 If defined, best practice to also define `revertCode`
     - ❗ If not defined `call` must be defined
     */
@@ -274,7 +1910,7 @@ pub struct FunctionData {
     - E.g. let's say `code` sets an environment variable as `setx POWERSHELL_TELEMETRY_OPTOUT 1`
       - then `revertCode` should be doing `setx POWERSHELL_TELEMETRY_OPTOUT 0`
     - 💡 [Expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-        can be used in code
+      can be used in code
     */
     #[serde(rename = "revertCode")]
     pub revert_code: Option<String>,
@@ -287,11 +1923,11 @@ pub struct FunctionData {
     /**
     - List of parameters that function code refers to.
     - ❗ Must be defined to be able use in [`FunctionCall`](FunctionCallData) or
-        [expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-    `code`: *`string`* (**required** if `call` is undefined)
+      [expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
+      `code`: *`string`* (**required** if `call` is undefined)
     - Batch file commands that will be executed
     - 💡 [Expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-        can be used in its value
+      can be used in its value
     - 💡 If defined, best practice to also define `revertCode`
     - ❗ If not defined `call` must be defined
     */
@@ -299,6 +1935,66 @@ pub struct FunctionData {
 }
 
 impl FunctionData {
+    /// Returns the names of the parameters that must be provided, i.e. neither `optional` nor with a `default`
+    pub fn required_parameters(&self) -> Vec<&str> {
+        self.parameters
+            .iter()
+            .flatten()
+            .filter(|pdd| !pdd.optional && pdd.default.is_none())
+            .map(|pdd| pdd.name.as_str())
+            .collect()
+    }
+
+    /// Returns the names of the `optional` parameters
+    pub fn optional_parameters(&self) -> Vec<&str> {
+        self.parameters
+            .iter()
+            .flatten()
+            .filter(|pdd| pdd.optional)
+            .map(|pdd| pdd.name.as_str())
+            .collect()
+    }
+
+    /// Checks whether the function defines revert code, directly or through all of its calls
+    fn is_revertable(&self, funcs: &Functions) -> bool {
+        match &self.call {
+            Some(fcd) => fcd.is_revertable(funcs),
+            None => self.revert_code.is_some(),
+        }
+    }
+
+    /// Names of the parameters used in the code, revert code or call parameter values, in order of first use
+    fn used_parameters(&self) -> Vec<&str> {
+        fn strings<'a>(value: &'a Value, texts: &mut Vec<&'a str>) {
+            match value {
+                Value::String(s) => texts.push(s),
+                Value::Sequence(seq) => seq.iter().for_each(|v| strings(v, texts)),
+                Value::Mapping(map) => map.values().for_each(|v| strings(v, texts)),
+                Value::Tagged(tagged) => strings(&tagged.value, texts),
+                _ => {}
+            }
+        }
+
+        let mut texts = self.code.iter().chain(&self.revert_code).map(String::as_str).collect();
+        for fcd in self.call.iter().flat_map(FunctionCallsData::as_slice) {
+            if let Some(params) = &fcd.parameters {
+                strings(params, &mut texts);
+            }
+        }
+
+        let variable = Regex::new(r"\{\{\s*(?:with\s+)?\$(\w+)").unwrap();
+        let mut used = Vec::new();
+        for text in texts {
+            for c in variable.captures_iter(text) {
+                let name = c.get(1).map_or("", |m| m.as_str());
+                if !used.contains(&name) {
+                    used.push(name);
+                }
+            }
+        }
+        used
+    }
+
     /**
     Parses [`FunctionData`] into String
 
@@ -309,10 +2005,37 @@ impl FunctionData {
     fn parse(
         &self,
         params: &Option<FunctionCallParametersData>,
-        funcs: &Option<Vec<FunctionData>>,
+        funcs: &Functions,
         os: OS,
         revert: bool,
     ) -> Result<String, ParseError> {
+        // A list value of a parameter renders the function once per element
+        if let Some(Value::Mapping(map)) = params {
+            let list = map.iter().find_map(|(k, v)| match v {
+                Value::Sequence(items)
+                    if self
+                        .parameters
+                        .iter()
+                        .flatten()
+                        .any(|pdd| k.as_str() == Some(&pdd.name)) =>
+                {
+                    Some((k, items))
+                }
+                _ => None,
+            });
+            if let Some((key, items)) = list {
+                return items
+                    .iter()
+                    .map(|item| {
+                        let mut map = map.clone();
+                        map.insert(key.clone(), item.clone());
+                        self.parse(&Some(Value::Mapping(map)), funcs, os, revert)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|blocks| blocks.join("\n"));
+            }
+        }
+
         let mut parsed = {
             if let Some(fcd) = &self.call {
                 fcd.parse(funcs, os, revert)?
@@ -324,24 +2047,39 @@ impl FunctionData {
         };
 
         if let Some(vec_pdd) = &self.parameters {
+            let context = Regex::new(r"\{\{\s*\.\s*(\||\}\})").unwrap();
             for pdd in vec_pdd {
-                parsed = match params.as_ref().and_then(|p| p.get(&pdd.name)) {
+                let name = regex::escape(&pdd.name);
+                let value = params
+                    .as_ref()
+                    .and_then(|p| p.get(&pdd.name))
+                    .filter(|v| !v.is_null())
+                    .cloned()
+                    .or_else(|| pdd.default.clone().map(Value::String));
+                parsed = match &value {
                     Some(v) => {
-                        if pdd.optional {
-                            parsed = Regex::new(&format!(
-                                r"(?s)\{{\{{\s*with\s*\${}\s*\}}\}}\s?(.*?)\s?\{{\{{\s*end\s*\}}\}}",
-                                &pdd.name
-                            ))
-                            .unwrap()
-                            .replace_all(&parsed, |c: &Captures| {
-                                c.get(1)
-                                    .map_or("", |m| m.as_str())
-                                    .replace("{{ . ", &format!("{{{{ ${} ", &pdd.name))
-                            })
-                            .to_string();
-                        }
+                        let v = match v {
+                            Value::String(s) => s.clone(),
+                            Value::Number(n) => n.to_string(),
+                            Value::Bool(b) => b.to_string(),
+                            _ => return Err(ParseError::ParameterValue(pdd.name.clone())),
+                        };
+
+                        parsed = Regex::new(&format!(
+                            r"(?s)\{{\{{\s*with\s*\${name}\s*\}}\}}\s?(.*?)\s?\{{\{{\s*end\s*\}}\}}"
+                        ))
+                        .unwrap()
+                        .replace_all(&parsed, |c: &Captures| {
+                            context
+                                .replace_all(c.get(1).map_or("", |m| m.as_str()), |c: &Captures| {
+                                    format!("{{{{ ${} {}", &pdd.name, c.get(1).map_or("", |m| m.as_str()))
+                                })
+                                .to_string()
+                        })
+                        .to_string();
 
-                        Regex::new(format!(r"\{{\{{\s*\${}\s*((\|\s*\w*\s*)*)\}}\}}", &pdd.name).as_str())
+                        let mut pipe_error = None;
+                        let substituted = Regex::new(&format!(r"\{{\{{\s*\${name}\s*((\|\s*\w*\s*)*)\}}\}}"))
                             .unwrap()
                             .replace_all(&parsed, |c: &Captures| {
                                 c.get(1)
@@ -349,14 +2087,22 @@ impl FunctionData {
                                     .split('|')
                                     .map(str::trim)
                                     .filter(|p| !p.is_empty())
-                                    .fold(v.as_str().unwrap().to_string(), |v, pipe| piper(pipe.trim(), &v))
-                            })
+                                    .try_fold(v.clone(), |v, pipe| piper(pipe, &v))
+                                    .unwrap_or_else(|e| {
+                                        pipe_error.get_or_insert(e);
+                                        String::new()
+                                    })
+                            });
+
+                        if let Some(e) = pipe_error {
+                            return Err(e);
+                        }
+                        substituted
                     }
                     None => {
                         if pdd.optional {
                             Regex::new(&format!(
-                                r"(?s)\{{\{{\s*with\s*\${}\s*\}}\}}\s?(.*?)\s?\{{\{{\s*end\s*\}}\}}",
-                                &pdd.name
+                                r"(?s)\{{\{{\s*with\s*\${name}\s*\}}\}}\s?(.*?)\s?\{{\{{\s*end\s*\}}\}}"
                             ))
                             .unwrap()
                             .replace_all(&parsed, "")
@@ -369,10 +2115,39 @@ impl FunctionData {
             }
         }
 
-        Ok(parsed)
+        let mut env_error = None;
+        let parsed = Regex::new(r#"\{\{\s*env\s+"(\w*)"\s*\}\}"#)
+            .unwrap()
+            .replace_all(&parsed, |c: &Captures| {
+                let name = c.get(1).map_or("", |m| m.as_str());
+                env::var(name).unwrap_or_else(|_| {
+                    env_error.get_or_insert_with(|| ParseError::EnvVar(name.to_string()));
+                    String::new()
+                })
+            })
+            .to_string();
+        if let Some(e) = env_error {
+            return Err(e);
+        }
+
+        Ok(
+            Regex::new(r#"(?s)\{\{\s*if\s+os\s*==\s*"(\w*)"\s*\}\}\s?(.*?)\s?\{\{\s*end\s*\}\}"#)
+                .unwrap()
+                .replace_all(&parsed, |c: &Captures| {
+                    if c.get(1).map_or("", |m| m.as_str()) == os.to_string() {
+                        c.get(2).map_or("", |m| m.as_str()).to_string()
+                    } else {
+                        String::new()
+                    }
+                })
+                .to_string(),
+        )
     }
 }
 
+/// [`FunctionData`] of a [`CollectionData`] by name
+type Functions<'a> = HashMap<&'a str, &'a FunctionData>;
+
 /**
 - Defines key value dictionary for each parameter and its value
 - E.g.
@@ -385,7 +2160,9 @@ impl FunctionData {
   ```
 
 - 💡 [Expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-    can be used as parameter value
+  can be used as parameter value
+- 💡 A list of values renders the function once per value, refer to
+  [list values](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#list-values)
 */
 pub type FunctionCallParametersData = serde_yaml::Value;
 
@@ -394,9 +2171,9 @@ pub type FunctionCallParametersData = serde_yaml::Value;
 
 - Describes a single call to a function by optionally providing values to its parameters.
 - 👀 See [parameter substitution](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution)
-    for an example usage
+  for an example usage
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FunctionCallData {
     /// - Name of the function to call.
     /// - ❗ Function with same name must defined in `functions` property of [Collection](CollectionData)
@@ -413,7 +2190,7 @@ pub struct FunctionCallData {
       ```
 
     - 💡 [Expressions (templating)](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#expressions)
-        can be used as parameter value
+      can be used as parameter value
     */
     pub parameters: Option<FunctionCallParametersData>,
 }
@@ -426,10 +2203,10 @@ impl FunctionCallData {
 
     Returns [`ParseError`] if the object is not parsable
     */
-    fn parse(&self, funcs: &Option<Vec<FunctionData>>, os: OS, revert: bool) -> Result<String, ParseError> {
+    fn parse(&self, funcs: &Functions, os: OS, revert: bool) -> Result<String, ParseError> {
+        debug!("resolving function {}", self.function);
         funcs
-            .as_ref()
-            .and_then(|vec_fd| vec_fd.iter().find(|fd| fd.name == self.function))
+            .get(self.function.as_str())
             .map_or(Err(ParseError::Function(self.function.clone())), |fd| {
                 fd.parse(&self.parameters, funcs, os, revert)
             })
@@ -437,7 +2214,7 @@ impl FunctionCallData {
 }
 
 /// Possible parameters of a function call i.e. either one parameter or multiple parameters
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum FunctionCallsData {
     /// Multiple Parameter
@@ -447,6 +2224,38 @@ pub enum FunctionCallsData {
 }
 
 impl FunctionCallsData {
+    /// Returns the calls as a slice, regardless of the variant
+    fn as_slice(&self) -> &[FunctionCallData] {
+        match self {
+            FunctionCallsData::VecFunctionCallData(vec_fcd) => vec_fcd,
+            FunctionCallsData::FunctionCallData(fcd) => slice::from_ref(fcd),
+        }
+    }
+
+    /// Iterates over the calls in order, regardless of the variant
+    pub fn iter(&self) -> slice::Iter<'_, FunctionCallData> {
+        self.as_slice().iter()
+    }
+
+    /// Returns the number of calls, `1` for [`FunctionCallsData::FunctionCallData`]
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Checks whether there are no calls, i.e. an empty [`FunctionCallsData::VecFunctionCallData`]
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Checks whether every called function can be reverted
+    fn is_revertable(&self, funcs: &Functions) -> bool {
+        self.iter().all(|fcd| {
+            funcs
+                .get(fcd.function.as_str())
+                .is_some_and(|fd| fd.is_revertable(funcs))
+        })
+    }
+
     /**
     Parses [`FunctionCallsData`] into String
 
@@ -454,7 +2263,7 @@ impl FunctionCallsData {
 
     Returns [`ParseError`] if the object is not parsable
     */
-    fn parse(&self, funcs: &Option<Vec<FunctionData>>, os: OS, revert: bool) -> Result<String, ParseError> {
+    fn parse(&self, funcs: &Functions, os: OS, revert: bool) -> Result<String, ParseError> {
         match &self {
             FunctionCallsData::VecFunctionCallData(vec_fcd) => Ok(vec_fcd
                 .iter()
@@ -480,7 +2289,7 @@ impl FunctionCallsData {
      - Must define `call` property but not `code` or `revertCode`
 - 🙏 For any new script, please add `revertCode` and `docs` values if possible.
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ScriptData {
     /// - Name of the script
     /// - ❗ Must be unique throughout the [Collection](CollectionData)
@@ -499,6 +2308,40 @@ pub struct ScriptData {
     */
     #[serde(rename = "revertCode")]
     pub revert_code: Option<String>,
+    /**
+    - Command that succeeds if the change done by `code` (or `call`) is already applied.
+    - If defined, the generated script runs it first and skips `code` if it succeeds, as rendered by
+      [`ScriptRenderer::skip_if_applied`], e.g. using `if .. then .. else` for shell scripts and `goto` for batch
+      files
+    - 💡 Allows running the generated script repeatedly, only applying missing changes
+    - ❗ Not used for `revertCode`
+    */
+    #[serde(rename = "checkCode")]
+    pub check_code: Option<String>,
+    /**
+    - Scripts with a higher priority are run first, regardless of their position in the collection, e.g. to
+      create a restore point before any other script
+    - Defaults to `0`, scripts with the same priority keep their order in the collection
+    - 💡 Consecutive scripts of the same top-level category are still grouped under one category header
+    */
+    #[serde(default)]
+    pub priority: i32,
+    /**
+    - Swaps the roles of `code` and `revertCode` (or of the code and revert code of `call`), for tweaks that
+      are inherently a revert, e.g. re-enabling something
+    - `revertCode` is used when parsing with `revert` unset and `code` when parsing with `revert` set, i.e.
+      `revert` always undoes what the script does by default
+    - `checkCode` is still only used when `revert` is unset, checking whether `revertCode` is already applied
+    */
+    #[serde(default, rename = "revertOnly")]
+    pub revert_only: bool,
+    /**
+    - Names of scripts that must run before this one
+    - Required scripts are included whenever this script is selected, even if they are not selected themselves,
+      and are parsed before it, or after it when reverting
+    - ❗ Scripts must not require each other, directly or indirectly
+    */
+    pub requires: Option<Vec<String>>,
     /// - A shared function or sequence of functions to call (called in order)
     /// - ❗ If not defined `code` must be defined
     pub call: Option<FunctionCallsData>,
@@ -510,37 +2353,82 @@ pub struct ScriptData {
     - If defined it can be either
       - `standard`: Only non-breaking scripts without limiting OS functionality
       - `strict`: Scripts that can break certain functionality in favor of privacy and security
+    - If not defined the `recommend` of the closest parent [category](CategoryData) defining one is used
     */
     pub recommend: Option<Recommend>,
 }
 
 impl ScriptData {
+    /// Checks whether the script defines revert code, directly or through all of its calls, or the code used to
+    /// revert it if `revertOnly`
+    fn is_revertable(&self, funcs: &Functions) -> bool {
+        match &self.call {
+            _ if self.revert_only => self.call.is_some() || self.code.is_some(),
+            Some(fcd) => fcd.is_revertable(funcs),
+            None => self.revert_code.is_some(),
+        }
+    }
+
+    /// Returns the documentation URLs of the script, if any
+    pub fn doc_urls(&self) -> Vec<&str> {
+        match &self.docs {
+            Some(DocumentationUrlsData::VecStrings(urls)) => urls.iter().map(String::as_str).collect(),
+            Some(DocumentationUrlsData::String(url)) => vec![url],
+            None => Vec::new(),
+        }
+    }
+
+    /**
+    Returns the literal `revertCode` if `revert`, otherwise `code`, as written in the collection
+
+    Unlike [`CollectionData::parse_one`], the code is neither expanded nor wrapped in a comment banner. Returns
+    `None` for scripts with a `call` and for scripts without the requested code.
+    */
+    pub fn raw_code(&self, revert: bool) -> Option<&str> {
+        if self.call.is_some() {
+            return None;
+        }
+        if revert {
+            self.revert_code.as_deref()
+        } else {
+            self.code.as_deref()
+        }
+    }
+
+    /// Checks whether the script is selected by `names` and `recommend`, using `inherited` as its recommend level
+    /// unless it defines its own
+    fn is_selected(
+        &self,
+        names: Option<&[String]>,
+        recommend: Option<Recommend>,
+        include_unrecommended: bool,
+        inherited: Option<Recommend>,
+    ) -> bool {
+        recommend.is_none_or(|level| {
+            self.recommend
+                .or(inherited)
+                .map_or(include_unrecommended, |r| level.includes(r))
+        }) && names.is_none_or(|ns| ns.contains(&self.name))
+    }
+
     /**
-    Parses [`ScriptData`] into String
+    Parses [`ScriptData`] into its code only, without [`beautify`]ing it
 
     # Errors
 
     Returns [`ParseError`] if the object is not parsable
     */
-    fn parse(
-        &self,
-        names: Option<&Vec<&str>>,
-        funcs: &Option<Vec<FunctionData>>,
-        os: OS,
-        revert: bool,
-        recommend: Option<Recommend>,
-    ) -> Result<String, ParseError> {
-        if (recommend.is_some() && recommend > self.recommend)
-            || names.map_or(false, |n| !n.contains(&self.name.as_str()))
-        {
-            Ok(String::new())
-        } else if let Some(fcd) = &self.call {
-            Ok(beautify(&fcd.parse(funcs, os, revert)?, &self.name, os, revert))
-        } else if let Some(code_string) = if revert { &self.revert_code } else { &self.code } {
-            Ok(beautify(code_string, &self.name, os, revert))
+    fn code(&self, funcs: &Functions, os: OS, revert: bool) -> Result<String, ParseError> {
+        let use_revert_code = revert != self.revert_only;
+        let code = if let Some(fcd) = &self.call {
+            fcd.parse(funcs, os, use_revert_code)?
+        } else if let Some(code_string) = if use_revert_code { &self.revert_code } else { &self.code } {
+            code_string.to_string()
         } else {
-            Err(ParseError::CallCode(self.name.clone()))
-        }
+            return Err(ParseError::CallCode(self.name.clone()));
+        };
+
+        Ok(code)
     }
 }
 
@@ -549,7 +2437,7 @@ impl ScriptData {
 
 - Defines global properties for scripting that's used throughout its parent [Collection](CollectionData).
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct ScriptingDefinitionData {
     /// Name of the Script
     pub language: String,
@@ -558,7 +2446,7 @@ pub struct ScriptingDefinitionData {
     pub file_extension: Option<String>,
     /**
     - Code that'll be inserted on top of user created script.
-    - Global variables such as `$homepage`, `$version`, `$date` can be used using
+    - Global variables such as `$homepage`, `$version`, `$date`, `$osName` can be used using
       [parameter substitution](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution)
       code syntax such as `Welcome to {{ $homepage }}!`
     */
@@ -566,12 +2454,33 @@ pub struct ScriptingDefinitionData {
     pub start_code: String,
     /**
     - Code that'll be inserted at the end of user created script.
-    - Global variables such as `$homepage`, `$version`, `$date` can be used using
+    - Global variables such as `$homepage`, `$version`, `$date`, `$osName` can be used using
       [parameter substitution](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution)
       code syntax such as `Welcome to {{ $homepage }}!`
     */
     #[serde(rename = "endCode")]
     pub end_code: String,
+    /// Optional command printing the name of each script, e.g. `Write-Host ---`, `echo ---` if not defined
+    #[serde(rename = "echoCommand")]
+    pub echo_command: Option<String>,
+}
+
+impl ScriptingDefinitionData {
+    /**
+    Returns the interpreter command for `language`, to be used with [`run_script_with`](crate::run_script_with)
+    - `shellscript` / `bash`: `bash`
+    - `batchfile`: `cmd /c`
+    - `powershell`: `pwsh -File`
+    - otherwise empty, i.e. the script is executed directly
+    */
+    pub fn default_interpreter(&self) -> &'static [&'static str] {
+        match self.language.as_str() {
+            "shellscript" | "bash" => &["bash"],
+            "batchfile" => &["cmd", "/c"],
+            "powershell" => &["pwsh", "-File"],
+            _ => &[],
+        }
+    }
 }
 
 /**
@@ -579,8 +2488,14 @@ pub struct ScriptingDefinitionData {
 - If defined it can be either
   - `standard`: Only non-breaking scripts without limiting OS functionality
   - `strict`: Scripts that can break certain functionality in favor of privacy and security
+- Requesting a level selects scripts as follows, refer to [`Recommend::includes`]
+
+  | requested / script | `standard` | `strict` | not recommended |
+  |--------------------|------------|----------|-----------------|
+  | `standard`         | ✅         | ❌       | ❌              |
+  | `strict`           | ✅         | ✅       | ❌              |
 */
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd)]
 pub enum Recommend {
     /// - `strict`: Scripts that can break certain functionality in favor of privacy and security
     #[serde(rename = "strict")]
@@ -589,3 +2504,14 @@ pub enum Recommend {
     #[serde(rename = "standard")]
     Standard,
 }
+
+impl Recommend {
+    /// Checks whether requesting `self` selects scripts recommended as `other`,
+    /// i.e. `strict` includes both levels while `standard` includes only itself
+    pub fn includes(self, other: Recommend) -> bool {
+        match self {
+            Recommend::Strict => true,
+            Recommend::Standard => other == Recommend::Standard,
+        }
+    }
+}
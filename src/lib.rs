@@ -9,18 +9,30 @@
 Note: This is a rust port of [privacy.sexy](https://github.com/undergroundwires/privacy.sexy)
 */
 pub mod collection;
+#[cfg(feature = "network")]
+mod network;
+pub mod prelude;
+pub mod renderer;
 mod util;
 
 use std::{
-    env, fmt, fs, io,
-    process::{Command, ExitStatus},
+    env,
+    ffi::OsStr,
+    fmt, fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process::{self, Child, Command, ExitStatus},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
 };
 
 use collection::{CollectionData, CollectionError};
 use serde::{Deserialize, Serialize};
+pub use util::function_name;
 
 /// Allowed values for OS
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OS {
     /// Apple
     #[serde(rename = "macos")]
@@ -51,6 +63,11 @@ impl OS {
     }
 }
 
+impl OS {
+    /// All supported [`OS`]es
+    pub const ALL: [OS; 3] = [OS::MacOs, OS::Windows, OS::Linux];
+}
+
 impl fmt::Display for OS {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -61,41 +78,369 @@ impl fmt::Display for OS {
     }
 }
 
+/// Environment variable naming a directory of `{os}.yaml` collections, refer to [`get_collection`]
+pub const COLLECTIONS_ENV: &str = "PRIVACY_SEXY_COLLECTIONS";
+
 /**
 Main way to get rules in form of [`CollectionData`]
 
+The collection is read from the first `{os}.yaml` file found in:
+1. the directory named by the [`PRIVACY_SEXY_COLLECTIONS`](COLLECTIONS_ENV) environment variable, if set
+2. the `privacy-sexy/collections` directory in the config directory of the current user, i.e.
+   `$XDG_CONFIG_HOME` or `~/.config` on Linux & other Unix-like systems, `~/Library/Application Support` on
+   macOS and `%APPDATA%` on Windows
+
+falling back to the [bundled collection](get_bundled_collection) otherwise, so it does not depend on the current
+working directory. Refer to [`find_collection_file`] for the file used, if any.
+
 # Errors
 
 Refer to [`CollectionError`]
 */
 pub fn get_collection(os: OS) -> Result<CollectionData, CollectionError> {
-    CollectionData::from_file(format!("collections/{os}.yaml"))
+    match find_collection_file(os) {
+        Some(file) => CollectionData::from_file(file),
+        None => get_bundled_collection(os),
+    }
+}
+
+/// Returns the `{os}.yaml` file [`get_collection`] reads the collection for `os` from, `None` if it falls back to the
+/// bundled collection
+pub fn find_collection_file(os: OS) -> Option<PathBuf> {
+    let config_dir = match env::consts::OS {
+        "macos" => env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support")),
+        "windows" => env::var_os("APPDATA").map(PathBuf::from),
+        _ if cfg!(unix) => env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))),
+        _ => None,
+    };
+
+    env::var_os(COLLECTIONS_ENV)
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(config_dir.map(|dir| dir.join("privacy-sexy").join("collections")))
+        .map(|dir| dir.join(format!("{os}.yaml")))
+        .find(|file| file.is_file())
+}
+
+/**
+Parses the collection for `os` bundled with the crate, embedded at compile time from `collections/{os}.yaml`
+
+Unlike [`get_collection`], this does not touch the filesystem and thus works regardless of the current working
+directory
+
+# Errors
+
+Refer to [`CollectionError`]
+*/
+pub fn get_bundled_collection(os: OS) -> Result<CollectionData, CollectionError> {
+    let yaml = match os {
+        OS::MacOs => include_str!("../collections/macos.yaml"),
+        OS::Windows => include_str!("../collections/windows.yaml"),
+        OS::Linux => include_str!("../collections/linux.yaml"),
+    };
+    CollectionData::from_reader(yaml.as_bytes())
+}
+
+/// Holds a [`CollectionData`] for each [`OS`], if available
+#[derive(Debug, Default)]
+pub struct CollectionSet {
+    macos: Option<CollectionData>,
+    windows: Option<CollectionData>,
+    linux: Option<CollectionData>,
+}
+
+impl CollectionSet {
+    /**
+    Reads [`CollectionData`] for every [`OS`] as by [`get_collection`]
+
+    # Errors
+
+    Refer to [`CollectionError`]
+    */
+    pub fn load_all() -> Result<Self, CollectionError> {
+        let mut set = CollectionSet::default();
+
+        for os in OS::ALL {
+            match get_collection(os) {
+                Ok(cd) => set.insert(cd),
+                Err(CollectionError::IOError(e)) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(set)
+    }
+
+    /// Returns [`CollectionData`] for `os`, if loaded
+    pub fn get(&self, os: OS) -> Option<&CollectionData> {
+        match os {
+            OS::MacOs => self.macos.as_ref(),
+            OS::Windows => self.windows.as_ref(),
+            OS::Linux => self.linux.as_ref(),
+        }
+    }
+
+    /// Inserts `cd` under its own [`OS`], replacing any previously held [`CollectionData`]
+    pub fn insert(&mut self, cd: CollectionData) {
+        let slot = match cd.os {
+            OS::MacOs => &mut self.macos,
+            OS::Windows => &mut self.windows,
+            OS::Linux => &mut self.linux,
+        };
+        *slot = Some(cd);
+    }
+}
+
+/**
+Converts every line ending in `script` (`\r\n`, `\r` or `\n`) to CRLF for [`OS::Windows`] and LF otherwise
+
+Scripts are normalized for the current OS before being run, refer to [`run_script`]
+*/
+pub fn normalize_line_endings(script: &str, os: OS) -> String {
+    let newline = if matches!(os, OS::Windows) { "\r\n" } else { "\n" };
+
+    let mut normalized = String::with_capacity(script.len());
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                normalized.push_str(newline);
+            }
+            '\n' => normalized.push_str(newline),
+            c => normalized.push(c),
+        }
+    }
+    normalized
 }
 
 /**
 Runs the script
 
+`file_extension` is usually [`CollectionData::file_extension`] of the collection the script was parsed from. The
+script is run with the interpreter of the scripting language using it, i.e. `bash` for `sh`, `cmd /c` for `bat` &
+`cmd` and `pwsh -File` for `ps1` as by
+[`ScriptingDefinitionData::default_interpreter`](collection::ScriptingDefinitionData::default_interpreter), and
+executed directly otherwise
+
+The temp script file is removed once the script exits, or if it cannot be run
+
 # Errors
 
 Returns [`Err`] if it is unable to:
 - write to the temp script file OR
 - change it's permissions (for unix) OR
-- execute the script
+- execute the script, e.g. if no interpreter is associated with `file_extension`, which is named in the error
 */
 pub fn run_script(script_string: &str, file_extension: Option<String>) -> Result<ExitStatus, io::Error> {
-    let mut tmp_file = env::temp_dir();
-    tmp_file.push("privacy-sexy");
+    let interpreter: &[&str] = match file_extension.as_deref() {
+        Some("sh") => &["bash"],
+        Some("bat" | "cmd") => &["cmd", "/c"],
+        Some("ps1") => &["pwsh", "-File"],
+        _ => &[],
+    };
+    run_script_with(script_string, file_extension, interpreter)
+}
+
+/**
+Runs the script by passing the temp script file to `interpreter`, e.g. `["pwsh", "-File"]`
+
+The script file is executed directly if `interpreter` is empty, refer to
+[`ScriptingDefinitionData::default_interpreter`](collection::ScriptingDefinitionData::default_interpreter)
+for an interpreter matching the scripting language of a collection
+
+The temp script file is removed once the script exits, or if it cannot be run
+
+# Errors
+
+Returns [`Err`] if it is unable to:
+- write to the temp script file OR
+- change it's permissions (for unix) OR
+- execute the script, e.g. if `interpreter` is not installed, which is named in the error
+*/
+pub fn run_script_with(
+    script_string: &str,
+    file_extension: Option<String>,
+    interpreter: &[&str],
+) -> Result<ExitStatus, io::Error> {
+    let tmp_file = write_script(&env::temp_dir(), script_string, file_extension)?;
+
+    match interpreter {
+        [] => spawn(&mut Command::new(&tmp_file), None, &tmp_file)?.wait(),
+        [program, args @ ..] => spawn(
+            Command::new(program).args(args).arg(&tmp_file),
+            Some(program),
+            &tmp_file,
+        )?
+        .wait(),
+    }
+}
+
+/**
+Runs the script from a script file written to `dir` instead of the temp directory, e.g. when the temp directory
+is mounted `noexec` on hardened systems
+
+The script file is removed from `dir` once the script exits
+
+# Errors
+
+Returns [`Err`] if:
+- `dir` is not an existing directory, of kind [`io::ErrorKind::NotFound`] OR
+- `dir` is not writable, of kind [`io::ErrorKind::PermissionDenied`] OR
+- it is unable to change the permissions of the script file (for unix) OR
+- it is unable to execute the script
+*/
+pub fn run_script_in(
+    dir: impl AsRef<Path>,
+    script_string: &str,
+    file_extension: Option<String>,
+) -> Result<ExitStatus, io::Error> {
+    let dir = dir.as_ref();
+    if !dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not an existing directory", dir.display()),
+        ));
+    }
+
+    let file = write_script(dir, script_string, file_extension).map_err(|e| match e.kind() {
+        io::ErrorKind::PermissionDenied => io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not writable: {e}", dir.display()),
+        ),
+        _ => e,
+    })?;
+    spawn(&mut Command::new(&file), None, &file)?.wait()
+}
+
+/**
+Runs the script, killing it if it does not finish within `timeout`
+
+On unix, the script is run in its own process group so that the processes it started are killed along with it.
+Elsewhere, only the script itself is killed.
+
+The temp script file is removed once the script exits or is killed
+
+# Errors
+
+Returns [`Err`] if it is unable to:
+- write to the temp script file OR
+- change it's permissions (for unix) OR
+- execute the script
+
+Returns [`Err`] of kind [`io::ErrorKind::TimedOut`] if the script exceeds `timeout`
+*/
+pub fn run_script_timeout(
+    script_string: &str,
+    file_extension: Option<String>,
+    timeout: Duration,
+) -> Result<ExitStatus, io::Error> {
+    let tmp_file = write_script(&env::temp_dir(), script_string, file_extension)?;
+
+    let mut command = Command::new(&tmp_file);
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    spawn(&mut command, None, &tmp_file).and_then(|mut child| {
+        let start = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            if start.elapsed() >= timeout {
+                kill_group(&mut child)?;
+                child.wait()?;
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "script timed out"));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    })
+}
+
+/// Kills `child` along with the processes it started, which share its process group on unix
+fn kill_group(child: &mut Child) -> Result<(), io::Error> {
+    #[cfg(target_family = "unix")]
+    {
+        let pgid = libc::pid_t::try_from(child.id()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: `kill` only sends a signal, to the process group `child` leads
+        if unsafe { libc::kill(-pgid, libc::SIGKILL) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    #[cfg(not(target_family = "unix"))]
+    child.kill()
+}
+
+/// Spawns `command` running the script `file`, with `interpreter` if not executed directly, describing the likely
+/// missing interpreter if it fails
+fn spawn(command: &mut Command, interpreter: Option<&str>, file: &Path) -> Result<Child, io::Error> {
+    command.spawn().map_err(|e| {
+        let reason = match interpreter {
+            Some(program) => format!("interpreter `{program}` may not be installed"),
+            None => match file.extension() {
+                Some(ext) => format!(
+                    "no interpreter may be associated with `.{}` files",
+                    ext.to_string_lossy()
+                ),
+                None => "it may not be executable or its interpreter may not be installed".to_string(),
+            },
+        };
+        io::Error::new(e.kind(), format!("unable to run {}, {reason}: {e}", file.display()))
+    })
+}
+
+/// Script file written by [`write_script`], removed once dropped
+struct ScriptFile(PathBuf);
+
+impl Deref for ScriptFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<OsStr> for ScriptFile {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl Drop for ScriptFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Writes `script_string` to a uniquely named, executable file in `dir`
+fn write_script(dir: &Path, script_string: &str, file_extension: Option<String>) -> Result<ScriptFile, io::Error> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut tmp_file = dir.to_path_buf();
+    tmp_file.push(format!(
+        "privacy-sexy-{}-{}",
+        process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
     if let Some(ext) = file_extension {
         tmp_file.set_extension(ext);
     }
 
-    fs::write(&tmp_file, script_string)?;
+    let os = if cfg!(windows) { OS::Windows } else { OS::Linux };
+    fs::write(&tmp_file, normalize_line_endings(script_string, os))?;
+    let tmp_file = ScriptFile(tmp_file);
 
     #[cfg(target_family = "unix")]
     {
         use std::os::unix::prelude::PermissionsExt;
-        fs::set_permissions(&tmp_file, fs::Permissions::from_mode(0o755))?;
+        fs::set_permissions(&tmp_file.0, fs::Permissions::from_mode(0o755))?;
     }
 
-    Command::new(tmp_file.to_str().unwrap_or_default()).spawn()?.wait()
+    Ok(tmp_file)
 }
@@ -1,8 +1,20 @@
-use clap::{Parser, Subcommand};
-use privacy_sexy::{self, collection::Recommend, OS};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process,
+};
+
+use clap::{ArgGroup, Parser, Subcommand};
+use privacy_sexy::{
+    self,
+    collection::{CollectionData, ParseOptions, Recommend, ValidationReport},
+    renderer, OS,
+};
 
 #[derive(Debug, Parser)]
-#[command(version)]
+#[command(version, group(ArgGroup::new("recommend").args(["strict", "standard", "recommended"])))]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -12,47 +24,233 @@ struct Cli {
     /// Recommend standard
     #[arg(short = 'd', long)]
     standard: bool,
+    /// Recommended at any level, i.e. standard or strict
+    #[arg(long)]
+    recommended: bool,
+    /// Also include scripts that are not recommended at any level
+    #[arg(long, requires = "recommend")]
+    include_unrecommended: bool,
     /// Name of script(s) required
     #[arg(short, long)]
     name: Vec<String>,
     /// Revert script(s)
     #[arg(short, long)]
     revert: bool,
+    /// Skip selected script(s) that cannot be reverted instead of aborting
+    #[arg(long, requires = "revert")]
+    skip_unrevertable: bool,
+    /// Prepend a UTF-8 BOM to the script(s) of a Windows collection
+    #[arg(long)]
+    bom: bool,
+    /// Collection file to use instead of the default one for the current OS
+    #[arg(short, long)]
+    file: Option<PathBuf>,
 }
 
 /// Commands
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Generate & print the script
-    Echo,
+    Echo {
+        /// Write each script to its own file in this directory instead of printing
+        #[arg(short, long)]
+        output_dir: Option<PathBuf>,
+        /// Do not colorize the script, it is only colorized when printed to a terminal
+        #[arg(long)]
+        no_color: bool,
+        /// Print the number of scripts matching the filters instead of the script
+        #[arg(long, conflicts_with = "output_dir")]
+        count: bool,
+    },
     /// Generate & run the script
     Run,
+    /// Validate collection(s) & check that they parse with every option
+    Validate {
+        /// Collection file(s) to validate, bundled collections if none are given
+        files: Vec<PathBuf>,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
-    let names = cli.name.iter().map(String::as_str).collect::<Vec<_>>();
-
-    let cd = privacy_sexy::get_collection(OS::get_system_os()).unwrap();
-
-    let script = cd
-        .parse(
-            if names.is_empty() { None } else { Some(&names) },
-            cli.revert,
-            if cli.strict {
-                Some(Recommend::Strict)
-            } else if cli.standard {
-                Some(Recommend::Standard)
+
+    if let Commands::Validate { files } = &cli.command {
+        process::exit(if validate(files) { 0 } else { 1 });
+    }
+
+    let cd = match &cli.file {
+        Some(file) => CollectionData::from_file(file),
+        None => privacy_sexy::get_collection(OS::get_system_os()),
+    }
+    .unwrap();
+
+    // strict includes scripts recommended at any level
+    let recommend = if cli.strict || cli.recommended {
+        Some(Recommend::Strict)
+    } else if cli.standard {
+        Some(Recommend::Standard)
+    } else {
+        None
+    };
+
+    let mut options = ParseOptions {
+        names: if cli.name.is_empty() { None } else { Some(cli.name) },
+        revert: cli.revert,
+        recommend,
+        include_unrecommended: cli.include_unrecommended,
+        bom: cli.bom,
+        ..Default::default()
+    };
+
+    if let Some(level) = recommend {
+        if cd.preview(&options).unwrap().is_empty() {
+            if cli.recommended {
+                eprintln!("Warning: no scripts are recommended");
             } else {
-                None
-            },
-        )
-        .unwrap();
+                eprintln!("Warning: no scripts are recommended as {level:?}");
+            }
+        }
+    }
+
+    if cli.revert {
+        let revertable = cd.list_revertable();
+        let (selected, unrevertable): (Vec<_>, Vec<_>) = cd
+            .preview(&options)
+            .unwrap()
+            .into_iter()
+            .map(|script| script.name.as_str())
+            .partition(|name| revertable.contains(name));
+
+        if !unrevertable.is_empty() {
+            eprintln!("The following script(s) cannot be reverted:");
+            for name in &unrevertable {
+                eprintln!("  - {name}");
+            }
+            if !cli.skip_unrevertable {
+                eprintln!("Aborting, use --skip-unrevertable to skip them");
+                process::exit(1);
+            }
+            options.names = Some(selected.into_iter().map(String::from).collect());
+        }
+    }
 
     match cli.command {
-        Commands::Echo => println!("{script}"),
+        Commands::Echo { count: true, .. } => println!("{}", cd.preview(&options).unwrap().len()),
+        Commands::Echo {
+            output_dir: Some(dir), ..
+        } => {
+            fs::create_dir_all(&dir).unwrap();
+            let mut stems = HashSet::new();
+            for (name, code) in cd.parse_each(&options).unwrap() {
+                // Distinct names such as `Clear bash history` & `Clear bash-history` share a function name
+                let base = privacy_sexy::function_name(&name);
+                let mut stem = base.clone();
+                for n in 2.. {
+                    if stems.insert(stem.clone()) {
+                        break;
+                    }
+                    stem = format!("{base}_{n}");
+                }
+                let file = dir.join(format!("{stem}.{}", cd.effective_file_extension()));
+                fs::write(file, privacy_sexy::normalize_line_endings(&code, cd.os)).unwrap();
+            }
+        }
+        Commands::Echo {
+            output_dir: None,
+            no_color,
+            ..
+        } => {
+            let script = cd.parse_with(&options).unwrap();
+            if !no_color && io::stdout().is_terminal() {
+                println!(
+                    "{}",
+                    renderer::get_renderer(&cd.scripting.language, cd.os).colorize(&script)
+                );
+            } else {
+                println!("{script}");
+            }
+        }
         Commands::Run => {
-            privacy_sexy::run_script(&script, cd.scripting.file_extension).unwrap();
+            let script = cd.parse_with(&options).unwrap();
+            let status = privacy_sexy::run_script_with(
+                &script,
+                Some(cd.effective_file_extension().to_string()),
+                cd.scripting.default_interpreter(),
+            )
+            .unwrap();
+            match status.code() {
+                Some(code) => eprintln!("Script exited with code {code}"),
+                None => eprintln!("Script was terminated by a signal"),
+            }
+            if !status.success() {
+                process::exit(status.code().unwrap_or(1));
+            }
+        }
+        Commands::Validate { .. } => unreachable!(),
+    }
+}
+
+/// Validates collections at `files`, or bundled collections if empty, returning whether all of them are valid
+fn validate(files: &[PathBuf]) -> bool {
+    let collections = if files.is_empty() {
+        OS::ALL
+            .into_iter()
+            .map(|os| {
+                (
+                    privacy_sexy::find_collection_file(os)
+                        .unwrap_or_else(|| PathBuf::from(format!("collections/{os}.yaml"))),
+                    privacy_sexy::get_collection(os),
+                )
+            })
+            .collect::<Vec<_>>()
+    } else {
+        files
+            .iter()
+            .map(|file| (file.clone(), CollectionData::from_file(file)))
+            .collect()
+    };
+
+    let mut all_valid = true;
+    for (file, cd) in collections {
+        let cd = match cd {
+            Ok(cd) => cd,
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                all_valid = false;
+                continue;
+            }
+        };
+
+        let mut valid = true;
+        if let Err(errors) = cd.validate() {
+            eprint!("{}:\n{}", file.display(), ValidationReport::from(errors));
+            valid = false;
+        }
+
+        let revertable = cd.list_revertable().into_iter().map(String::from).collect::<Vec<_>>();
+        for revert in [false, true] {
+            for recommend in [None, Some(Recommend::Standard), Some(Recommend::Strict)] {
+                let options = ParseOptions {
+                    names: revert.then(|| revertable.clone()),
+                    revert,
+                    recommend,
+                    ..Default::default()
+                };
+                if let Err(e) = cd.parse_with(&options) {
+                    eprintln!(
+                        "{}: parsing with revert: {revert}, recommend: {recommend:?} failed: {e:?}",
+                        file.display()
+                    );
+                    valid = false;
+                }
+            }
         }
+
+        if valid {
+            println!("{}: OK", file.display());
+        }
+        all_valid &= valid;
     }
+
+    all_valid
 }
@@ -0,0 +1,184 @@
+//! Fetching of [`CollectionData`] over the network, requires the `network` feature
+
+use std::{
+    fs::{self, File},
+    path::Path,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::debug;
+use reqwest::{
+    blocking::{Client, Response},
+    header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    IntoUrl, StatusCode,
+};
+
+use crate::collection::{CollectionData, CollectionError};
+
+/// Timeout of requests fetching a [`CollectionData`], refer to [`CollectionData::from_url`]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the 64-bit FNV-1a hash of `bytes`, which unlike [`std::hash::DefaultHasher`] is stable across Rust
+/// releases and thus suitable for naming cache files
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl CollectionData {
+    /**
+    Fetches [`CollectionData`] from `url`, giving up after [`DEFAULT_TIMEOUT`]
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - `url` cannot be fetched OR
+    - contents cannot be deserialized into [`CollectionData`]
+    */
+    pub fn from_url(url: impl IntoUrl) -> Result<CollectionData, CollectionError> {
+        CollectionData::from_url_with_timeout(url, DEFAULT_TIMEOUT)
+    }
+
+    /**
+    Fetches [`CollectionData`] from `url`, giving up if the request does not complete within `timeout`
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - `url` cannot be fetched, e.g. it times out OR
+    - contents cannot be deserialized into [`CollectionData`]
+    */
+    pub fn from_url_with_timeout(url: impl IntoUrl, timeout: Duration) -> Result<CollectionData, CollectionError> {
+        let url = url.into_url()?;
+        debug!("fetching collection from {url}");
+        let response = Client::builder().timeout(timeout).build()?.get(url).send()?;
+        Ok(serde_yaml::from_slice::<CollectionData>(&response.bytes()?)?.loaded())
+    }
+
+    /**
+    Fetches [`CollectionData`] from `url`, retrying up to `retries` times on transient failures
+
+    Timeouts, connection failures and `5xx` responses are retried after waiting `backoff`, doubled after each
+    retry. Other failures such as `404` responses or contents that cannot be deserialized fail immediately.
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - `url` cannot be fetched, with the last error once retries are exhausted OR
+    - contents cannot be deserialized into [`CollectionData`]
+    */
+    pub fn from_url_retry(
+        url: impl IntoUrl,
+        retries: u32,
+        backoff: Duration,
+    ) -> Result<CollectionData, CollectionError> {
+        let url = url.into_url()?;
+        let mut delay = backoff;
+        let mut attempt = 0;
+
+        loop {
+            let response = Client::new()
+                .get(url.clone())
+                .send()
+                .and_then(Response::error_for_status);
+            match response {
+                Ok(response) => {
+                    return Ok(serde_yaml::from_slice::<CollectionData>(&response.bytes()?)?.loaded());
+                }
+                Err(e)
+                    if attempt < retries
+                        && (e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| s.is_server_error())) =>
+                {
+                    debug!("fetching collection from {url} failed: {e}, retrying in {delay:?}");
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /**
+    Fetches [`CollectionData`] from `url`, caching the fetched contents in `cache_dir`
+
+    Contents are cached in a file named after a hash of `url` and read from there on subsequent calls,
+    unless the cached file is older than `max_age`. Stale contents are revalidated using the `ETag` and
+    `Last-Modified` headers of the cached response, and kept if the server replies `304 Not Modified`.
+
+    # Errors
+
+    Returns [`CollectionError`] if:
+    - `url` cannot be fetched OR
+    - cached file cannot be read or written OR
+    - contents cannot be deserialized into [`CollectionData`]
+    */
+    pub fn from_url_cached(
+        url: impl IntoUrl,
+        cache_dir: impl AsRef<Path>,
+        max_age: Option<Duration>,
+    ) -> Result<CollectionData, CollectionError> {
+        let url = url.into_url()?;
+        let cache_file = cache_dir
+            .as_ref()
+            .join(format!("{:016x}.yaml", fnv1a(url.as_str().as_bytes())));
+
+        let etag_file = cache_file.with_extension("etag");
+        let last_modified_file = cache_file.with_extension("last-modified");
+
+        let mut request = Client::new().get(url.clone());
+        if let Ok(modified) = fs::metadata(&cache_file).and_then(|m| m.modified()) {
+            if max_age.is_none_or(|max_age| modified.elapsed().is_ok_and(|age| age < max_age)) {
+                debug!("using cached collection for {url} from {}", cache_file.display());
+                return CollectionData::from_reader(File::open(cache_file)?);
+            }
+            debug!("revalidating stale cached collection for {url}");
+
+            if let Ok(etag) = fs::read_to_string(&etag_file) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Ok(last_modified) = fs::read_to_string(&last_modified_file) {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send()?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            debug!("cached collection is not modified, using {}", cache_file.display());
+            File::options()
+                .write(true)
+                .open(&cache_file)?
+                .set_modified(SystemTime::now())?;
+            return CollectionData::from_reader(File::open(cache_file)?);
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(String::from)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+
+        let bytes = response.bytes()?;
+        let cd = serde_yaml::from_slice::<CollectionData>(&bytes)?.loaded();
+        debug!("caching fetched collection in {}", cache_file.display());
+        fs::create_dir_all(cache_dir)?;
+        fs::write(cache_file, bytes)?;
+        for (file, value) in [(etag_file, etag), (last_modified_file, last_modified)] {
+            match value {
+                Some(value) => fs::write(file, value)?,
+                None => {
+                    if file.exists() {
+                        fs::remove_file(file)?;
+                    }
+                }
+            }
+        }
+
+        Ok(cd)
+    }
+}
@@ -0,0 +1,21 @@
+/*!
+Re-exports of the most frequently used items, to be glob imported
+
+```
+use privacy_sexy::prelude::*;
+
+let cd: CollectionData = get_bundled_collection(OS::Linux).unwrap();
+let script = cd
+    .parse_with(&ParseOptions {
+        recommend: Some(Recommend::Strict),
+        ..Default::default()
+    })
+    .unwrap();
+assert!(script.starts_with("#!/usr/bin/env bash"));
+```
+*/
+
+pub use crate::{
+    collection::{CollectionData, CollectionError, ParseError, ParseOptions, Recommend},
+    get_bundled_collection, get_collection, run_script, OS,
+};
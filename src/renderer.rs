@@ -0,0 +1,238 @@
+/*!
+Renders the comments & echo commands wrapped around each script, depending on the scripting language
+
+Renderers are looked up by [`ScriptingDefinitionData::language`](crate::collection::ScriptingDefinitionData::language)
+in a global registry, which ships with renderers for `batchfile`, `shellscript` / `bash` and `powershell`.
+Renderers for other languages can be added with [`register_renderer`]:
+
+```
+use privacy_sexy::renderer::{self, ScriptRenderer};
+
+struct Lua;
+
+impl ScriptRenderer for Lua {
+    fn comment_line(&self, text: &str) -> String {
+        format!("-- {text}")
+    }
+
+    fn echo(&self, text: &str) -> String {
+        format!("print('--- {text}')")
+    }
+}
+
+renderer::register_renderer("lua", Lua);
+assert_eq!(renderer::get_renderer("lua", privacy_sexy::OS::Linux).comment_line("hi"), "-- hi");
+```
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use crate::{util, OS};
+
+/// Width of the lines of a [`ScriptRenderer::banner`], without the comment prefix
+const BANNER_WIDTH: usize = 60;
+
+/// Renders the comments & echo commands of a scripting language
+pub trait ScriptRenderer: Send + Sync {
+    /// Returns `text` as a single line comment, e.g. `# text`
+    fn comment_line(&self, text: &str) -> String;
+
+    /// Returns a command printing `text` prefixed with `---`, e.g. `echo --- text`
+    fn echo(&self, text: &str) -> String;
+
+    /// Returns three comment lines with `title` centered in the middle one, padded with `fill`
+    fn banner(&self, title: &str, fill: char) -> String {
+        let line = fill.to_string().repeat(BANNER_WIDTH);
+        let padding = BANNER_WIDTH.saturating_sub(title.chars().count());
+        let title = format!(
+            "{}{title}{}",
+            fill.to_string().repeat(padding / 2),
+            fill.to_string().repeat(padding - padding / 2)
+        );
+        format!(
+            "{}\n{}\n{}",
+            self.comment_line(&line),
+            self.comment_line(&title),
+            self.comment_line(&line)
+        )
+    }
+
+    /// Returns `code` without its comment lines, by default lines starting with a `#`, `::` or `REM` comment except
+    /// shebangs
+    fn strip_comments(&self, code: &str) -> String {
+        util::strip_comments(code)
+    }
+
+    /// Returns a library defining each `(name, code)` of `functions` as a function, separated by empty lines, as POSIX
+    /// shell functions by default
+    fn function_library(&self, functions: &[(String, String)]) -> String {
+        functions
+            .iter()
+            .map(|(name, code)| format!("{name}() {{\n{code}\n}}"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Returns `code` skipped if `check_code` succeeds, as a POSIX shell `if` statement by default. The label is
+    /// unique to the script, for languages that jump over `code` instead
+    fn skip_if_applied(&self, code: &str, check_code: &str, _label: &str) -> String {
+        let code = if code.trim().is_empty() { ":" } else { code };
+        format!("if ( {check_code} ) >/dev/null 2>&1; then\n  echo 'Skipping, already applied'\nelse\n{code}\nfi")
+    }
+
+    /// Colors comment lines of `script` cyan, and the lines of banners naming a script bold yellow, leaving shebangs
+    /// and code as is
+    fn colorize(&self, script: &str) -> String {
+        let comment = self.comment_line("");
+        let prefix = comment.trim_end();
+        script
+            .lines()
+            .map(|line| match line.strip_prefix(prefix) {
+                Some(comment) if !comment.starts_with('!') => {
+                    if comment.starts_with(" -") && !comment.trim_matches([' ', '-']).is_empty() {
+                        format!("\x1b[1;33m{line}\x1b[0m")
+                    } else {
+                        format!("\x1b[36m{line}\x1b[0m")
+                    }
+                }
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Renderer for batch files, with `::` comments
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BatchRenderer;
+
+impl ScriptRenderer for BatchRenderer {
+    fn comment_line(&self, text: &str) -> String {
+        format!(":: {text}")
+    }
+
+    fn echo(&self, text: &str) -> String {
+        format!("echo --- {text}")
+    }
+
+    /// Defines functions as labels to be called with `call :<name>`, skipped by a leading `goto :eof` so that running
+    /// the library itself runs none of them
+    fn function_library(&self, functions: &[(String, String)]) -> String {
+        let mut library = vec!["goto :eof".to_string()];
+        library.extend(functions.iter().map(|(name, code)| format!(":{name}\n{code}\nexit /b")));
+        library.join("\n\n")
+    }
+
+    fn skip_if_applied(&self, code: &str, check_code: &str, label: &str) -> String {
+        format!(
+            "({check_code}) >nul 2>&1 && echo Skipping, already applied && goto :skip_{label}\n{code}\n:skip_{label}"
+        )
+    }
+}
+
+/// Renderer for shell scripts, with `#` comments
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BashRenderer;
+
+impl ScriptRenderer for BashRenderer {
+    fn comment_line(&self, text: &str) -> String {
+        format!("# {text}")
+    }
+
+    fn echo(&self, text: &str) -> String {
+        format!("echo --- {text}")
+    }
+}
+
+/// Renderer for PowerShell scripts, with `#` comments & `Write-Host`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PowerShellRenderer;
+
+impl ScriptRenderer for PowerShellRenderer {
+    fn comment_line(&self, text: &str) -> String {
+        format!("# {text}")
+    }
+
+    fn echo(&self, text: &str) -> String {
+        format!("Write-Host --- {text}")
+    }
+
+    /// Removes `#` comment lines except shebangs & `#Requires` statements, and `<# .. #>` block comments keeping any
+    /// code following them
+    fn strip_comments(&self, code: &str) -> String {
+        let mut in_block = false;
+        let mut kept = Vec::new();
+        for line in code.lines() {
+            let trimmed = line.trim_start();
+            let rest = if in_block {
+                line
+            } else if let Some(rest) = trimmed.strip_prefix("<#") {
+                in_block = true;
+                rest
+            } else {
+                let directive =
+                    trimmed.starts_with("#!") || trimmed.get(..9).is_some_and(|d| d.eq_ignore_ascii_case("#requires"));
+                if !trimmed.starts_with('#') || directive {
+                    kept.push(line);
+                }
+                continue;
+            };
+
+            if let Some((_, after)) = rest.split_once("#>") {
+                in_block = false;
+                if !after.trim().is_empty() {
+                    kept.push(after.trim_start());
+                }
+            }
+        }
+        kept.join("\n")
+    }
+
+    fn function_library(&self, functions: &[(String, String)]) -> String {
+        functions
+            .iter()
+            .map(|(name, code)| format!("function {name} {{\n{code}\n}}"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn skip_if_applied(&self, code: &str, check_code: &str, _label: &str) -> String {
+        format!("{check_code} *> $null\nif ($?) {{\n  Write-Host 'Skipping, already applied'\n}} else {{\n{code}\n}}")
+    }
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn ScriptRenderer>>>;
+
+/// Returns the global registry, initialized with the default renderers
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut renderers = HashMap::<String, Arc<dyn ScriptRenderer>>::new();
+        renderers.insert("batchfile".to_string(), Arc::new(BatchRenderer));
+        renderers.insert("shellscript".to_string(), Arc::new(BashRenderer));
+        renderers.insert("bash".to_string(), Arc::new(BashRenderer));
+        renderers.insert("powershell".to_string(), Arc::new(PowerShellRenderer));
+        RwLock::new(renderers)
+    })
+}
+
+/// Registers `renderer` for collections with `language`, replacing any renderer previously registered for it
+pub fn register_renderer(language: &str, renderer: impl ScriptRenderer + 'static) {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(language.to_string(), Arc::new(renderer));
+}
+
+/// Returns the renderer registered for `language`, or [`BatchRenderer`] for [`OS::Windows`] and [`BashRenderer`]
+/// for the rest if there is none
+pub fn get_renderer(language: &str, os: OS) -> Arc<dyn ScriptRenderer> {
+    match registry().read().unwrap_or_else(|e| e.into_inner()).get(language) {
+        Some(renderer) => Arc::clone(renderer),
+        None if os == OS::Windows => Arc::new(BatchRenderer),
+        None => Arc::new(BashRenderer),
+    }
+}
@@ -1,13 +1,15 @@
 use std::fs;
 
 use chrono::Local;
+use log::debug;
 use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 
-use crate::OS;
+use crate::{collection::ParseError, renderer::ScriptRenderer, OS};
 
 /**
-Wraps the `code_string` in comments and adds an echo call
+Wraps the `code_string` in comments and adds an echo call as rendered by `renderer`, using `echo_command` instead
+of [`ScriptRenderer::echo`] if given
 
 # Examples
 
@@ -20,60 +22,157 @@ echo --- Clear bash history
 rm -f ~/.bash_history
 ## ------------------------------------------------------------
 "#,
-beautify("rm -f ~/.bash_history", "Clear bash history", &OS::Linux, false)
+beautify("rm -f ~/.bash_history", "Clear bash history", &BashRenderer, false, None)
 )
 ```
 */
-pub fn beautify(code_string: &str, name: &str, os: OS, revert: bool) -> String {
+pub fn beautify(
+    code_string: &str,
+    name: &str,
+    renderer: &dyn ScriptRenderer,
+    revert: bool,
+    echo_command: Option<&str>,
+) -> String {
     let mut name = name.to_string();
     if revert {
         name.push_str(" (revert)");
     }
 
-    if let OS::Windows = os {
-        format!(
-            ":: {0:-^60}\n:: {1:-^60}\n:: {0:-^60}\necho --- {1}\n{2}\n:: {0:-^60}",
-            "", name, code_string
-        )
-    } else {
-        format!(
-            "# {0:-^60}\n# {1:-^60}\n# {0:-^60}\necho --- {1}\n{2}\n# {0:-^60}",
-            "", name, code_string
-        )
-    }
+    let echo = match echo_command {
+        Some(echo_command) => format!("{echo_command} {name}"),
+        None => renderer.echo(&name),
+    };
+    format!(
+        "{}\n{echo}\n{code_string}\n{}",
+        renderer.banner(&name, '-'),
+        renderer.comment_line(&"-".repeat(60))
+    )
+}
+
+/**
+Removes lines of `code` that are `#` (except shebangs), `::` or `REM` comments
+
+# Examples
+
+```ignore
+assert_eq!("#!/bin/sh\necho hi", strip_comments("#!/bin/sh\n# Greet\necho hi"));
+```
+*/
+pub fn strip_comments(code: &str) -> String {
+    code.lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            let rem = line.get(..3).is_some_and(|r| r.eq_ignore_ascii_case("rem"))
+                && line[3..].chars().next().is_none_or(char::is_whitespace);
+            !((line.starts_with('#') && !line.starts_with("#!")) || line.starts_with("::") || rem)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/**
+Wraps the category `name` in comments as rendered by `renderer`
+
+# Examples
+
+```ignore
+assert_eq!(r#"
+# ============================================================
+# =======================Privacy cleanup======================
+# ============================================================
+"#,
+category_header("Privacy cleanup", &BashRenderer)
+)
+```
+*/
+pub fn category_header(name: &str, renderer: &dyn ScriptRenderer) -> String {
+    renderer.banner(name, '=')
+}
+
+/**
+Converts the script `name` to lowercase, replacing each run of characters other than letters and digits with `_`
+
+# Examples
+
+```
+assert_eq!("clear_bash_history", privacy_sexy::function_name("Clear bash history"));
+```
+*/
+pub fn function_name(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_lowercase()
 }
 
+/// Re-runs the script with `sudo` unless already root, refer to [`ParseOptions::sudo`](crate::collection::ParseOptions::sudo)
+pub const SUDO_CHECK: &str = "if [ \"$(id -u)\" -ne 0 ]; then\n  exec sudo \"$0\" \"$@\"\nfi";
+
+/// UTF-8 byte order mark, refer to [`ParseOptions::bom`](crate::collection::ParseOptions::bom)
+pub const BOM: &str = "\u{feff}";
+
+/// Names of all available pipes, any other pipe name is rejected by [`piper`]
+pub const PIPES: [&str; 4] = [
+    "escapeDoubleQuotes",
+    "inlinePowerShell",
+    "powerShellSingleQuote",
+    "regEscape",
+];
+
 /**
 Applies pipe on `text`. Following pipes are available:
 - escapeDoubleQuotes
 - inlinePowerShell
+- powerShellSingleQuote
+- regEscape
 
-# Panics
+# Errors
 
-Panics for invalid regex expressions
+Returns [`ParseError`] if:
+- the pipe is not one of [`PIPES`] OR
+- the pipe fails to apply
 
 # Examples
 
 ```ignore
-assert_eq!("\"^\"\"Hello\"^\"\"", piper("escapeDoubleQuotes", "\"Hello\""));
+assert_eq!("\"^\"\"Hello\"^\"\"", piper("escapeDoubleQuotes", "\"Hello\"")?);
 ```
 */
-pub fn piper(pipe: &str, text: &str) -> String {
+pub fn piper(pipe: &str, text: &str) -> Result<String, ParseError> {
+    if !PIPES.contains(&pipe) {
+        return Err(ParseError::UnknownPipe(pipe.to_string()));
+    }
+    debug!("applying pipe {pipe}");
+
+    let regex = |re: &str| Regex::new(re).map_err(|_| ParseError::Pipe(pipe.to_string()));
+
     match pipe {
-        "escapeDoubleQuotes" => text.replace('\"', "\"^\"\""),
+        "escapeDoubleQuotes" => Ok(text.replace('\"', "\"^\"\"")),
+        "powerShellSingleQuote" => {
+            // PowerShell also ends single quoted strings at curly single quotes, escaped by doubling them too
+            let mut escaped = String::with_capacity(text.len());
+            for c in text.chars() {
+                escaped.push(c);
+                if matches!(c, '\'' | '\u{2018}'..='\u{201B}') {
+                    escaped.push(c);
+                }
+            }
+            Ok(escaped)
+        }
+        "regEscape" => Ok(reg_escape(text)),
         "inlinePowerShell" => {
+            let newline = regex(r"\r\n|\r|\n")?;
+
             // Inline comments
-            let t = Regex::new(r"<#\s*(.*)#>|#\s*(.*)")
-                .unwrap()
-                .replace_all(text, |c: &Captures| {
-                    c.get(1)
-                        .map_or(String::new(), |m| format!("<# {} #>", m.as_str().trim()))
-                });
+            let t = regex(r"<#\s*([^\r\n]*)#>|#[^\r\n]*")?.replace_all(text, |c: &Captures| {
+                c.get(1)
+                    .map_or(String::new(), |m| format!("<# {} #>", m.as_str().trim()))
+            });
 
             // Here strings
-            let t = Regex::new(r#"@(['"])\s*(?:\r\n|\r|\n)((.|\n|\r)+?)(\r\n|\r|\n)['"]@"#)
-                .unwrap()
-                .replace_all(&t, |c: &Captures| {
+            let t =
+                regex(r#"@(['"])\s*(?:\r\n|\r|\n)((.|\n|\r)+?)(\r\n|\r|\n)['"]@"#)?.replace_all(&t, |c: &Captures| {
                     let (quotes, escaped_quotes, separator) = match c.get(1).map_or("'", |m| m.as_str()) {
                         "'" => ("'", "''", "'+\"`r`n\"+'"),
                         _ => ("\"", "`\"", "`r`n"),
@@ -82,8 +181,7 @@ pub fn piper(pipe: &str, text: &str) -> String {
                     format!(
                         "{0}{1}{0}",
                         quotes,
-                        Regex::new(r"\r\n|\r|\n")
-                            .unwrap()
+                        newline
                             .split(&c.get(2).map_or("", |m| m.as_str()).replace(quotes, escaped_quotes))
                             .collect::<Vec<&str>>()
                             .join(separator)
@@ -91,21 +189,57 @@ pub fn piper(pipe: &str, text: &str) -> String {
                 });
 
             // Merge lines with back tick
-            let t = Regex::new(r" +`\s*(?:\r\n|\r|\n)\s*").unwrap().replace_all(&t, " ");
+            let t = regex(r" +`\s*(?:\r\n|\r|\n)\s*")?.replace_all(&t, " ");
 
             // Merge lines
-            Regex::new(r"\r\n|\r|\n")
-                .unwrap()
+            Ok(newline
                 .split(&t)
                 .map(str::trim)
                 .filter(|l| !l.is_empty())
                 .collect::<Vec<&str>>()
-                .join("; ")
+                .join("; "))
         }
-        _ => text.to_string(),
+        _ => Err(ParseError::UnknownPipe(pipe.to_string())),
     }
 }
 
+/**
+Escapes `text` to be used inside a double quoted argument of `reg` in a batch file
+- `\` followed by `"` or ending `text` is doubled, other `\` are kept as they are e.g. in registry paths
+- `"` is escaped as `\"`
+- `%` is escaped as `%%`
+
+# Examples
+
+```ignore
+assert_eq!(r#"HKCU\Say \"%%hi%%\"\\"#, reg_escape(r#"HKCU\Say "%hi%"\"#));
+```
+*/
+fn reg_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut backslashes = 0;
+    for c in text.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                escaped.push_str(&"\\".repeat(backslashes * 2 + 1));
+                escaped.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                escaped.push_str(&"\\".repeat(backslashes));
+                if c == '%' {
+                    escaped.push('%');
+                }
+                escaped.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    escaped.push_str(&"\\".repeat(backslashes * 2));
+    escaped
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 struct CargoParams {
     #[serde(default)]
@@ -121,23 +255,28 @@ struct PkgParams {
 }
 
 /**
-Substitutes global variables in `code_string`
+Substitutes global variables in `code_string` of a collection for `os`
 
 Supported global variables:
-- $date
+- $date, `date` if given, otherwise the current time in RFC 2822 format
 - $homepage
 - $version
+- $osName, e.g. `windows`
 
 Refer to [parameter substitution](https://github.com/SubconsciousCompute/privacy-sexy/blob/master/src/README.md#parameter-substitution)
 for more info & usage examples
 */
-pub fn parse_start_end(code_string: &str) -> String {
+pub fn parse_start_end(code_string: &str, os: OS, date: Option<&str>) -> String {
     let cargo_params =
         toml::from_str::<CargoParams>(&fs::read_to_string("Cargo.toml").unwrap_or_default()).unwrap_or_default();
 
     code_string
         .to_string()
-        .replace("{{ $date }}", &Local::now().to_rfc2822())
+        .replace(
+            "{{ $date }}",
+            &date.map_or_else(|| Local::now().to_rfc2822(), str::to_string),
+        )
         .replace("{{ $homepage }}", &cargo_params.package.homepage)
         .replace("{{ $version }}", &cargo_params.package.version)
+        .replace("{{ $osName }}", &os.to_string())
 }
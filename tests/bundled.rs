@@ -0,0 +1,52 @@
+use std::{env, fs, path::Path};
+
+use privacy_sexy::{
+    collection::CollectionData, find_collection_file, get_bundled_collection, get_collection, COLLECTIONS_ENV, OS,
+};
+
+#[test]
+fn get_bundled_collection_test() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("collections");
+    let expected = OS::ALL.map(|os| CollectionData::from_file(dir.join(format!("{os}.yaml"))).unwrap());
+
+    env::set_current_dir(env::temp_dir()).unwrap();
+    for (os, expected) in OS::ALL.into_iter().zip(expected) {
+        assert_eq!(get_bundled_collection(os).unwrap(), expected);
+    }
+}
+
+#[test]
+fn collections_env_test() {
+    let dir = env::temp_dir().join(format!("privacy-sexy-collections-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("linux.yaml"),
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Custom script
+        code: echo custom
+",
+    )
+    .unwrap();
+
+    env::set_var(COLLECTIONS_ENV, &dir);
+    assert_eq!(find_collection_file(OS::Linux), Some(dir.join("linux.yaml")));
+    let cd = get_collection(OS::Linux).unwrap();
+    assert!(cd.find_script("Custom script").is_some());
+
+    // Collections missing from the directory fall back to the bundled ones
+    assert_eq!(
+        get_collection(OS::MacOs).unwrap(),
+        get_bundled_collection(OS::MacOs).unwrap()
+    );
+
+    env::remove_var(COLLECTIONS_ENV);
+    fs::remove_dir_all(dir).unwrap();
+}
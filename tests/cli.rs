@@ -0,0 +1,258 @@
+use std::{env, fs, process::Command};
+
+fn cli() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_privacy-sexy"))
+}
+
+#[test]
+fn validate_bundled_test() {
+    let output = cli().arg("validate").output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().matches(": OK").count(), 3);
+}
+
+#[test]
+fn validate_file_test() {
+    let file = env::temp_dir().join(format!("privacy-sexy-validate-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: NoSuchFunction
+",
+    )
+    .unwrap();
+
+    let output = cli().arg("validate").arg(&file).output().unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("NoSuchFunction"));
+    assert!(stderr.contains("parsing with revert: false, recommend: None failed"));
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn run_exit_code_test() {
+    let file = env::temp_dir().join(format!("privacy-sexy-run-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: '#!/bin/sh'
+  endCode: exit 0
+actions:
+  - category: Category
+    children:
+      - name: Failing script
+        code: exit 3
+",
+    )
+    .unwrap();
+
+    let output = cli().arg("--file").arg(&file).arg("run").output().unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Script exited with code 3"));
+}
+
+#[test]
+fn revert_unrevertable_test() {
+    let file = env::temp_dir().join(format!("privacy-sexy-revert-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Revertable
+        code: echo apply
+        revertCode: echo revert
+      - name: Unrevertable
+        code: echo apply
+",
+    )
+    .unwrap();
+
+    let aborted = cli()
+        .arg("--file")
+        .arg(&file)
+        .args(["--revert", "echo"])
+        .output()
+        .unwrap();
+    let skipped = cli()
+        .arg("--file")
+        .arg(&file)
+        .args(["--revert", "--skip-unrevertable", "echo"])
+        .output()
+        .unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert!(!aborted.status.success());
+    assert!(aborted.stdout.is_empty());
+    let stderr = String::from_utf8(aborted.stderr).unwrap();
+    assert!(stderr.contains("  - Unrevertable"));
+    assert!(!stderr.contains("  - Revertable"));
+
+    assert!(skipped.status.success());
+    let stdout = String::from_utf8(skipped.stdout).unwrap();
+    assert!(stdout.contains("echo revert"));
+    assert!(!stdout.contains("Unrevertable"));
+}
+
+#[test]
+fn recommended_test() {
+    let file = env::temp_dir().join(format!("privacy-sexy-recommended-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Standard script
+        code: echo standard
+        recommend: standard
+      - name: Strict script
+        code: echo strict
+        recommend: strict
+      - name: Unrecommended script
+        code: echo unrecommended
+",
+    )
+    .unwrap();
+
+    let echo = |args: &[&str]| cli().arg("--file").arg(&file).args(args).arg("echo").output().unwrap();
+    let recommended = echo(&["--recommended"]);
+    let all = echo(&["--recommended", "--include-unrecommended"]);
+    let conflict = echo(&["--recommended", "--standard"]);
+    let unrequired = echo(&["--include-unrecommended"]);
+    let empty = echo(&["--standard", "--name", "Strict script"]);
+    fs::remove_file(&file).unwrap();
+
+    let stdout = String::from_utf8(recommended.stdout).unwrap();
+    assert!(stdout.contains("echo standard") && stdout.contains("echo strict"));
+    assert!(!stdout.contains("echo unrecommended"));
+    assert!(!String::from_utf8(recommended.stderr).unwrap().contains("Warning"));
+
+    let stdout = String::from_utf8(all.stdout).unwrap();
+    assert!(
+        stdout.contains("echo standard") && stdout.contains("echo strict") && stdout.contains("echo unrecommended")
+    );
+
+    assert!(!conflict.status.success());
+    assert!(String::from_utf8(conflict.stderr)
+        .unwrap()
+        .contains("cannot be used with"));
+    assert!(!unrequired.status.success());
+    assert!(String::from_utf8(empty.stderr)
+        .unwrap()
+        .contains("Warning: no scripts are recommended as Standard"));
+}
+
+#[test]
+fn output_dir_test() {
+    let dir = env::temp_dir().join(format!("privacy-sexy-output-test-{}", std::process::id()));
+    let file = env::temp_dir().join(format!("privacy-sexy-output-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: First script
+        code: echo first
+      - name: Second/script?
+        code: echo second
+      - name: Third script
+        code: echo third
+      - name: Third-script
+        code: echo third again
+",
+    )
+    .unwrap();
+
+    let output = cli()
+        .arg("--file")
+        .arg(&file)
+        .args(["echo", "--output-dir"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    fs::remove_file(file).unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let mut names = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    let second = fs::read_to_string(dir.join("second_script.sh")).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+
+    assert_eq!(
+        names,
+        [
+            "first_script.sh",
+            "second_script.sh",
+            "third_script.sh",
+            "third_script_2.sh"
+        ]
+    );
+    assert!(second.starts_with("start\n"));
+    assert!(second.contains("\necho second\n"));
+    assert!(second.ends_with("\nend"));
+}
+
+#[test]
+fn count_test() {
+    let count = |args: &[&str]| {
+        let output = cli()
+            .args(["-f", "collections/macos.yaml"])
+            .args(args)
+            .args(["echo", "--count"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim()
+            .parse::<usize>()
+            .unwrap()
+    };
+
+    assert_eq!(count(&["-n", "Clear bash history", "-n", "Clear zsh history"]), 2);
+    assert_eq!(count(&["-n", "Clear terminal history"]), 2);
+    assert!(count(&["--standard"]) < count(&["--strict"]));
+}
@@ -1,10 +1,19 @@
-use privacy_sexy::collection::CollectionData;
+use std::{collections::HashMap, env, fs, io::Cursor};
+
+use privacy_sexy::{
+    collection::{
+        CategoryData, CategoryOrScriptData, CollectionData, CollectionDiff, CollectionError, FunctionCallsData,
+        Overrides, ParseError, Recommend, SchemaError, TemplateStats, SCHEMA_VERSION,
+    },
+    CollectionSet, OS,
+};
 
 #[test]
 fn from_file_test() {
     assert!(CollectionData::from_file("collections/macos.yaml").is_ok());
 }
 
+#[cfg(feature = "network")]
 #[test]
 fn from_url_test() {
     assert!(CollectionData::from_url(
@@ -12,3 +21,800 @@ fn from_url_test() {
     )
     .is_ok());
 }
+
+#[test]
+fn find_script_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let script = cd.find_script("Clear bash history").unwrap();
+    assert_eq!(script.name, "Clear bash history");
+    assert_eq!(script.code.as_deref(), Some("rm -f ~/.bash_history"));
+
+    assert!(cd.find_script("Clear terminal history").is_none());
+    assert!(cd.find_script("No such script").is_none());
+}
+
+#[test]
+fn find_category_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let category = cd.find_category("Clear terminal history").unwrap();
+    assert_eq!(category.category, "Clear terminal history");
+    assert_eq!(category.children.len(), 2);
+
+    assert!(cd.find_category("Clear bash history").is_none());
+    assert!(cd.find_category("No such category").is_none());
+}
+
+#[test]
+fn collection_set_test() {
+    let set = CollectionSet::load_all().unwrap();
+
+    for os in OS::ALL {
+        assert_eq!(set.get(os).unwrap().os.to_string(), os.to_string());
+    }
+}
+
+#[test]
+fn os_recommend_hash_test() {
+    let mut scripts = HashMap::new();
+    for os in OS::ALL {
+        let cd = CollectionData::from_file(format!("collections/{os}.yaml")).unwrap();
+        scripts.insert(os, cd.iter_scripts().count());
+    }
+    assert_eq!(scripts.len(), 3);
+    assert!(scripts[&OS::Linux] > 0);
+    assert_eq!(OS::Windows, OS::Windows);
+    assert_ne!(OS::Windows, OS::MacOs);
+
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    let mut counts = HashMap::<Option<Recommend>, usize>::new();
+    for (_, script) in cd.iter_scripts() {
+        *counts.entry(cd.recommend_of(&script.name).unwrap()).or_default() += 1;
+    }
+    assert_eq!(counts.values().sum::<usize>(), cd.iter_scripts().count());
+    assert!(counts[&Some(Recommend::Strict)] > 0);
+}
+
+#[test]
+fn iter_scripts_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let (path, _) = cd
+        .iter_scripts()
+        .find(|(_, script)| script.name == "Clear diagnostics logs")
+        .unwrap();
+    assert_eq!(
+        path,
+        vec!["Privacy cleanup", "Clear OS logs", "Clear unified logs (diagnostics)"]
+    );
+
+    let (path, script) = cd.iter_scripts().next().unwrap();
+    assert_eq!(path, vec!["Privacy cleanup", "Clear terminal history"]);
+    assert_eq!(script.name, "Clear bash history");
+}
+
+#[test]
+fn search_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let scripts = cd.search("HISTORY");
+    assert!(scripts.len() > 1);
+    assert!(scripts.iter().any(|script| script.name == "Clear bash history"));
+    assert!(scripts
+        .iter()
+        .any(|script| script.name == "Clear Safari browsing history"));
+
+    assert!(cd
+        .search("eclecticlight")
+        .iter()
+        .any(|script| script.name == "Clear diagnostics logs"));
+    assert!(cd.search("no such tweak").is_empty());
+}
+
+#[test]
+fn scripting_accessors_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    assert_eq!(cd.language(), "shellscript");
+    assert_eq!(cd.file_extension(), None);
+}
+
+#[test]
+fn has_recommend_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Standard script
+        recommend: standard
+        code: echo standard
+      - name: Unrecommended script
+        code: echo unrecommended
+"#,
+    )
+    .unwrap();
+
+    assert!(cd.has_recommend(Recommend::Standard));
+    assert!(!cd.has_recommend(Recommend::Strict));
+}
+
+#[test]
+fn recommend_of_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Strict script
+        recommend: strict
+        code: echo strict
+      - name: Unrecommended script
+        code: echo unrecommended
+"#,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        cd.recommend_of("Strict script"),
+        Some(Some(Recommend::Strict))
+    ));
+    assert!(matches!(cd.recommend_of("Unrecommended script"), Some(None)));
+    assert!(cd.recommend_of("No such script").is_none());
+}
+
+#[test]
+fn from_reader_test() {
+    let yaml = b"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo script
+";
+
+    let cd = CollectionData::from_reader(Cursor::new(yaml)).unwrap();
+    assert_eq!(cd.language(), "shellscript");
+    assert!(cd.find_script("Script").is_some());
+
+    assert!(CollectionData::from_reader(Cursor::new(b"os: [")).is_err());
+}
+
+#[test]
+fn from_combined_file_test() {
+    let mut combined = serde_yaml::Mapping::new();
+    for os in OS::ALL {
+        let mut value =
+            serde_yaml::to_value(CollectionData::from_file(format!("collections/{os}.yaml")).unwrap()).unwrap();
+        value.as_mapping_mut().unwrap().remove("os");
+        combined.insert(os.to_string().into(), value);
+    }
+
+    let file = env::temp_dir().join(format!("privacy-sexy-combined-test-{}.yaml", std::process::id()));
+    fs::write(&file, serde_yaml::to_string(&combined).unwrap()).unwrap();
+
+    for os in OS::ALL {
+        let expected =
+            serde_yaml::to_value(CollectionData::from_file(format!("collections/{os}.yaml")).unwrap()).unwrap();
+        let cd = CollectionData::from_combined_file(&file, os).unwrap();
+        assert_eq!(serde_yaml::to_value(cd).unwrap(), expected);
+
+        // Single collection files are accepted as well
+        let cd = CollectionData::from_combined_file(format!("collections/{os}.yaml"), os).unwrap();
+        assert_eq!(serde_yaml::to_value(cd).unwrap(), expected);
+    }
+
+    fs::remove_file(file).unwrap();
+
+    assert!(matches!(
+        CollectionData::from_combined_file("collections/macos.yaml", OS::Linux),
+        Err(CollectionError::OSNotFound(OS::Linux))
+    ));
+}
+
+#[test]
+fn from_file_strict_test() {
+    let file = env::temp_dir().join(format!("privacy-sexy-strict-test-{}.yaml", std::process::id()));
+    fs::write(
+        &file,
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo code
+        revertCde: echo revert
+",
+    )
+    .unwrap();
+
+    let cd = CollectionData::from_file(&file).unwrap();
+    assert!(cd.find_script("Script").unwrap().revert_code.is_none());
+    let err = CollectionData::from_file_strict(&file).unwrap_err();
+
+    fs::remove_file(file).unwrap();
+
+    assert!(matches!(&err, CollectionError::UnknownField(path) if path == "actions[0].children[0].revertCde"));
+}
+
+#[test]
+fn from_dir_test() {
+    let dir = env::temp_dir().join(format!("privacy-sexy-dir-test-{}", std::process::id()));
+    fs::create_dir_all(dir.join("categories")).unwrap();
+
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    let mut value = serde_yaml::to_value(&cd).unwrap();
+    let actions = value.as_mapping_mut().unwrap().remove("actions").unwrap();
+    fs::write(dir.join("collection.yaml"), serde_yaml::to_string(&value).unwrap()).unwrap();
+    for (i, category) in actions.as_sequence().unwrap().iter().enumerate() {
+        fs::write(
+            dir.join("categories").join(format!("{i:02}.yaml")),
+            serde_yaml::to_string(category).unwrap(),
+        )
+        .unwrap();
+    }
+    fs::write(dir.join("categories").join("README.md"), "not a category").unwrap();
+
+    let split = CollectionData::from_dir(&dir);
+
+    fs::remove_dir_all(dir).unwrap();
+
+    assert_eq!(
+        serde_yaml::to_value(split.unwrap()).unwrap(),
+        serde_yaml::to_value(cd).unwrap()
+    );
+}
+
+#[test]
+fn clone_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    let mut clone = cd.clone();
+
+    clone.actions.truncate(1);
+    clone.scripting.start_code = String::new();
+
+    assert_eq!(cd.actions.len(), 3);
+    assert!(!cd.scripting.start_code.is_empty());
+    assert_eq!(clone.actions[0].category, cd.actions[0].category);
+    assert!(clone.iter_scripts().count() < cd.iter_scripts().count());
+}
+
+#[test]
+fn eq_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    assert_eq!(cd, CollectionData::from_file("collections/linux.yaml").unwrap());
+    assert_ne!(cd, CollectionData::from_file("collections/macos.yaml").unwrap());
+
+    let mut modified = cd.clone();
+    assert_eq!(modified, cd);
+    modified.actions[0].recommend = Some(Recommend::Strict);
+    assert_ne!(modified, cd);
+    assert_eq!(modified.actions[1], cd.actions[1]);
+}
+
+#[test]
+fn filter_test() {
+    fn assert_not_empty(category: &CategoryData) {
+        assert!(!category.children.is_empty());
+        for child in &category.children {
+            if let CategoryOrScriptData::CategoryData(data) = child {
+                assert_not_empty(data);
+            }
+        }
+    }
+
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    let strict = cd.filter(|script| script.recommend == Some(Recommend::Strict));
+
+    assert!(strict.iter_scripts().count() > 0);
+    assert!(strict
+        .iter_scripts()
+        .all(|(_, script)| script.recommend == Some(Recommend::Strict)));
+    assert_eq!(
+        strict.iter_scripts().count(),
+        cd.iter_scripts()
+            .filter(|(_, script)| script.recommend == Some(Recommend::Strict))
+            .count()
+    );
+    strict.actions.iter().for_each(assert_not_empty);
+}
+
+#[test]
+fn to_file_test() {
+    let cd = CollectionData::from_file("collections/windows.yaml")
+        .unwrap()
+        .filter(|script| script.docs.is_some());
+
+    let from_str: CollectionData = serde_yaml::from_str(&cd.to_yaml_string().unwrap()).unwrap();
+    assert_eq!(
+        serde_yaml::to_value(from_str).unwrap(),
+        serde_yaml::to_value(&cd).unwrap()
+    );
+
+    let file = env::temp_dir().join(format!("privacy-sexy-to-file-test-{}.yaml", std::process::id()));
+    cd.to_file(&file).unwrap();
+    let from_file = CollectionData::from_file(&file);
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(
+        serde_yaml::to_value(from_file.unwrap()).unwrap(),
+        serde_yaml::to_value(&cd).unwrap()
+    );
+}
+
+#[test]
+fn diff_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    assert_eq!(cd.diff(&cd.clone()), CollectionDiff::default());
+
+    let mut modified = cd.clone();
+    let CategoryOrScriptData::CategoryData(category) = &mut modified.actions[0].children[0] else {
+        panic!("expected a category");
+    };
+    let CategoryOrScriptData::ScriptData(removed) = category.children.remove(0) else {
+        panic!("expected a script");
+    };
+    let CategoryOrScriptData::ScriptData(changed) = &mut category.children[0] else {
+        panic!("expected a script");
+    };
+    changed.revert_code = Some("echo revert".to_string());
+    let changed = changed.name.clone();
+    let mut added = removed.clone();
+    added.name = "Added script".to_string();
+    modified.actions[1]
+        .children
+        .push(CategoryOrScriptData::ScriptData(added));
+
+    assert_eq!(
+        cd.diff(&modified),
+        CollectionDiff {
+            added: vec!["Added script".to_string()],
+            removed: vec![removed.name.clone()],
+            changed: vec![changed.clone()],
+        }
+    );
+    assert_eq!(
+        modified.diff(&cd),
+        CollectionDiff {
+            added: vec![removed.name],
+            removed: vec!["Added script".to_string()],
+            changed: vec![changed],
+        }
+    );
+}
+
+#[test]
+fn effective_file_extension_test() {
+    let collection = |os: &str, language: &str, file_extension: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            "
+os: {os}
+scripting:
+  language: {language}
+  fileExtension: {file_extension}
+  startCode: start
+  endCode: end
+actions: []
+"
+        ))
+        .unwrap()
+    };
+
+    for os in OS::ALL {
+        let cd = CollectionData::from_file(format!("collections/{os}.yaml")).unwrap();
+        let expected = if matches!(os, OS::Windows) { "bat" } else { "sh" };
+        assert_eq!(cd.effective_file_extension(), expected);
+
+        assert_eq!(
+            collection(&os.to_string(), "unknown", "~").effective_file_extension(),
+            expected
+        );
+        assert_eq!(
+            collection(&os.to_string(), "powershell", "~").effective_file_extension(),
+            "ps1"
+        );
+        assert_eq!(
+            collection(&os.to_string(), "bash", "~").effective_file_extension(),
+            "sh"
+        );
+        assert_eq!(
+            collection(&os.to_string(), "bash", "zsh").effective_file_extension(),
+            "zsh"
+        );
+    }
+}
+
+#[test]
+fn function_parameters_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions: []
+functions:
+  - name: Mixed
+    parameters:
+      - name: first
+      - name: second
+        optional: true
+      - name: third
+        optional: false
+      - name: fourth
+        optional: true
+      - name: fifth
+        default: 1
+      - name: sixth
+        default: true
+    code: echo {{ $first }} {{ $second }} {{ $third }} {{ $fourth }} {{ $fifth }} {{ $sixth }}
+  - name: None
+    code: echo none
+",
+    )
+    .unwrap();
+    let functions = cd.functions.unwrap();
+
+    assert_eq!(functions[0].required_parameters(), ["first", "third"]);
+    assert_eq!(functions[0].optional_parameters(), ["second", "fourth"]);
+    assert!(functions[1].required_parameters().is_empty());
+    assert!(functions[1].optional_parameters().is_empty());
+    let defaults = functions[0]
+        .parameters
+        .iter()
+        .flatten()
+        .map(|pdd| pdd.default.as_deref());
+    assert!(defaults.eq([None, None, None, None, Some("1"), Some("true")]));
+}
+
+#[test]
+fn describe_script_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo enable
+        revertCode: echo disable
+        recommend: standard
+        docs:
+          - ' https://example.com/one '
+          - https://example.com/two
+      - name: Unrevertable
+        code: echo code
+",
+    )
+    .unwrap();
+
+    let description = cd.describe_script("Script").unwrap();
+    assert_eq!(description.name, "Script");
+    assert!(description.code.contains("\necho enable\n"));
+    assert!(description.revert_code.unwrap().contains("\necho disable\n"));
+    assert_eq!(description.recommend, Some(Recommend::Standard));
+    assert_eq!(
+        description.doc_urls,
+        ["https://example.com/one", "https://example.com/two"]
+    );
+
+    let description = cd.describe_script("Unrevertable").unwrap();
+    assert!(description.revert_code.is_none());
+    assert!(description.recommend.is_none());
+    assert!(description.doc_urls.is_empty());
+
+    assert!(cd.describe_script("No such script").is_err());
+}
+
+#[test]
+fn apply_overrides_test() {
+    let mut cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    let overrides: Overrides = serde_yaml::from_str(
+        "
+disable:
+  - Clear bash history
+recommend:
+  Clear zsh history: strict
+",
+    )
+    .unwrap();
+
+    cd.apply_overrides(&overrides);
+
+    assert!(cd.find_script("Clear bash history").is_none());
+    assert!(!cd.parse(None, false, None).unwrap().contains("Clear bash history"));
+    assert!(matches!(
+        cd.recommend_of("Clear zsh history"),
+        Some(Some(Recommend::Strict))
+    ));
+}
+
+#[test]
+fn imports_test() {
+    let dir = env::temp_dir().join(format!("privacy-sexy-imports-test-{}", std::process::id()));
+    fs::create_dir_all(dir.join("shared")).unwrap();
+    fs::write(
+        dir.join("shared").join("functions.yaml"),
+        "
+functions:
+  - name: SharedFunction
+    code: echo shared
+",
+    )
+    .unwrap();
+    let collection = |function: &str| {
+        format!(
+            "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: SharedFunction
+functions:
+  - name: {function}
+    code: echo local
+imports:
+  - shared/functions.yaml
+"
+        )
+    };
+    fs::write(dir.join("collection.yaml"), collection("LocalFunction")).unwrap();
+    let cd = CollectionData::from_file(dir.join("collection.yaml"));
+    fs::write(dir.join("collection.yaml"), collection("SharedFunction")).unwrap();
+    let duplicate = CollectionData::from_file(dir.join("collection.yaml"));
+    fs::write(dir.join("collection.yaml"), collection("LocalFunction")).unwrap();
+    let strict = CollectionData::from_file_strict(dir.join("collection.yaml"));
+    let combined = CollectionData::from_combined_file(dir.join("collection.yaml"), OS::Linux);
+
+    fs::remove_dir_all(dir).unwrap();
+
+    let cd = cd.unwrap();
+    assert!(cd.imports.is_none());
+    assert_eq!(cd.functions.as_ref().unwrap().len(), 2);
+    assert!(cd.parse(None, false, None).unwrap().contains("\necho shared\n"));
+    assert!(matches!(duplicate, Err(CollectionError::DuplicateFunction(name)) if name == "SharedFunction"));
+    for cd in [strict.unwrap(), combined.unwrap()] {
+        assert!(cd.imports.is_none());
+        assert_eq!(cd.functions.as_ref().unwrap().len(), 2);
+    }
+}
+
+#[test]
+fn round_trip_bundled_test() {
+    for os in OS::ALL {
+        let cd = CollectionData::from_file(format!("collections/{os}.yaml")).unwrap();
+        let round_tripped = CollectionData::try_from(cd.to_yaml_string().unwrap().as_str()).unwrap();
+        assert_eq!(round_tripped, cd);
+    }
+}
+
+#[test]
+fn template_stats_test() {
+    let collection = |call_c: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: A
+functions:
+  - name: A
+    call:
+      - function: B
+        parameters:
+          file: a
+      - function: C
+  - name: B
+    parameters:
+      - name: file
+      - name: mode
+        optional: true
+    call:
+      function: C
+  - name: C
+    {call_c}
+  - name: D
+    parameters:
+      - name: value
+    code: echo {{{{ $value }}}}
+"
+        ))
+        .unwrap()
+    };
+
+    assert_eq!(
+        collection("code: echo c").template_stats().unwrap(),
+        TemplateStats {
+            parameterized_functions: 2,
+            parameters: 3,
+            max_call_depth: 3,
+        }
+    );
+    assert!(matches!(
+        collection("call:\n      function: A").template_stats(),
+        Err(ParseError::CallCycle(cycle)) if cycle == ["A", "B", "C", "A"]
+    ));
+    assert!(matches!(
+        collection("call:\n      function: E").template_stats(),
+        Err(ParseError::Function(name)) if name == "E"
+    ));
+}
+
+#[test]
+fn scripts_without_revert_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Revertable script
+        code: echo code
+        revertCode: echo revert
+      - name: Irreversible script
+        code: echo code
+      - name: Revertable caller
+        call:
+          function: Revertable
+      - name: Irreversible caller
+        call:
+          - function: Revertable
+          - function: Irreversible
+functions:
+  - name: Revertable
+    code: echo code
+    revertCode: echo revert
+  - name: Irreversible
+    code: echo code
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        cd.scripts_without_revert(),
+        ["Irreversible script", "Irreversible caller"]
+    );
+    assert_eq!(cd.list_revertable(), ["Revertable script", "Revertable caller"]);
+
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    assert_eq!(
+        cd.scripts_without_revert().len() + cd.list_revertable().len(),
+        cd.iter_scripts().count()
+    );
+}
+
+#[test]
+fn metadata_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+title: Test tweaks
+description: Tweaks used in tests
+schemaVersion: 1.0.0
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo script
+",
+    )
+    .unwrap();
+
+    assert_eq!(cd.title.as_deref(), Some("Test tweaks"));
+    assert_eq!(cd.description.as_deref(), Some("Tweaks used in tests"));
+    assert_eq!(cd.schema_version.as_deref(), Some("1.0.0"));
+
+    let file = env::temp_dir().join(format!("privacy-sexy-metadata-test-{}.yaml", std::process::id()));
+    fs::write(&file, serde_yaml::to_string(&cd).unwrap()).unwrap();
+    assert_eq!(CollectionData::from_file_strict(&file).unwrap(), cd);
+    fs::remove_file(file).unwrap();
+
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    assert!(cd.title.is_none());
+    assert!(cd.description.is_none());
+    assert!(cd.schema_version.is_none());
+}
+
+#[test]
+fn check_schema_version_test() {
+    let mut cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    assert!(cd.check_schema_version(SCHEMA_VERSION).is_ok());
+
+    let mut check = |version: &str, supported: &str| {
+        cd.schema_version = Some(version.to_string());
+        cd.check_schema_version(supported)
+    };
+
+    // Compatible
+    assert!(check("1.2.0", "1.2.0").is_ok());
+    assert!(check("1.0.5", "1.2.0").is_ok());
+    assert!(check("0.3.1", "0.3.0").is_ok());
+    // Minor ahead
+    assert!(check("1.3.0", "1.2.0").is_ok());
+    // Major ahead
+    assert!(matches!(
+        check("2.0.0", "1.2.0"),
+        Err(SchemaError::Incompatible { found, supported }) if found == "2.0.0" && supported == "1.2.0"
+    ));
+    assert!(matches!(check("0.4.0", "0.3.0"), Err(SchemaError::Incompatible { .. })));
+    assert!(matches!(check("0.9.0", "1.0.0"), Err(SchemaError::Incompatible { .. })));
+    // Invalid
+    assert!(matches!(
+        check("1.x", "1.0.0"),
+        Err(SchemaError::InvalidVersion(version)) if version == "1.x"
+    ));
+    assert!(matches!(check("1.0.0", "1"), Err(SchemaError::InvalidVersion(_))));
+    assert!(check("1.1.0-beta.1", "1.1.0").is_ok());
+}
+
+#[test]
+fn function_calls_iter_test() {
+    let single: FunctionCallsData = serde_yaml::from_str("function: A").unwrap();
+    assert!(matches!(single, FunctionCallsData::FunctionCallData(_)));
+    assert_eq!(single.len(), 1);
+    assert!(!single.is_empty());
+    assert_eq!(
+        single.iter().map(|fcd| fcd.function.as_str()).collect::<Vec<_>>(),
+        ["A"]
+    );
+
+    let multiple: FunctionCallsData =
+        serde_yaml::from_str("[{ function: A }, { function: B }, { function: C }]").unwrap();
+    assert!(matches!(multiple, FunctionCallsData::VecFunctionCallData(_)));
+    assert_eq!(multiple.len(), 3);
+    assert_eq!(
+        multiple.iter().map(|fcd| fcd.function.as_str()).collect::<Vec<_>>(),
+        ["A", "B", "C"]
+    );
+
+    let empty: FunctionCallsData = serde_yaml::from_str("[]").unwrap();
+    assert_eq!(empty.len(), 0);
+    assert!(empty.is_empty());
+}
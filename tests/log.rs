@@ -0,0 +1,39 @@
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use privacy_sexy::collection::CollectionData;
+
+/// Captures every record logged by the crate
+struct CapturingLogger(Mutex<Vec<(Level, String)>>);
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target().starts_with("privacy_sexy") {
+            self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+#[test]
+fn load_log_test() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    cd.parse(None, false, None).unwrap();
+
+    let records = LOGGER.0.lock().unwrap();
+    assert!(records.contains(&(
+        Level::Info,
+        format!("loaded linux collection with {} scripts", cd.iter_scripts().count())
+    )));
+    assert!(records.contains(&(Level::Debug, format!("selected {} scripts", cd.iter_scripts().count()))));
+}
@@ -1,7 +1,7 @@
 use std::fs;
 
 use glob::glob;
-use privacy_sexy::collection::CollectionData;
+use privacy_sexy::collection::{CollectionData, ParseError, ParseOptions, ParseStats, Recommend, SortOrder};
 
 #[test]
 fn parse_test() {
@@ -18,3 +18,1921 @@ fn parse_test() {
         );
     }
 }
+
+#[test]
+fn category_headers_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            category_headers: true,
+            ..Default::default()
+        })
+        .unwrap();
+    for category in &cd.actions {
+        assert!(script.contains(&format!("# {:=^60}", category.category)));
+    }
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            names: Some(vec!["Clear bash history".to_string()]),
+            category_headers: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(script.contains(&format!("# {:=^60}", "Privacy cleanup")));
+    assert!(!script.contains(&format!("# {:=^60}", "Configure programs")));
+}
+
+#[test]
+fn if_os_test() {
+    let yaml = r#"
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Clear temp files
+        call:
+          function: ClearTempFiles
+functions:
+  - name: ClearTempFiles
+    code: |-
+      {{ if os == "windows" }}
+      del /q /s %TEMP%\*
+      {{ end }}
+      {{ if os == "linux" }}
+      rm -rf /tmp/*
+      {{ end }}
+"#;
+
+    let windows = serde_yaml::from_str::<CollectionData>(&format!("os: windows{yaml}"))
+        .unwrap()
+        .parse(None, false, None)
+        .unwrap();
+    assert!(windows.contains(r"del /q /s %TEMP%\*"));
+    assert!(!windows.contains("rm -rf /tmp/*"));
+    assert!(!windows.contains("{{"));
+
+    let linux = serde_yaml::from_str::<CollectionData>(&format!("os: linux{yaml}"))
+        .unwrap()
+        .parse(None, false, None)
+        .unwrap();
+    assert!(linux.contains("rm -rf /tmp/*"));
+    assert!(!linux.contains(r"del /q /s %TEMP%\*"));
+    assert!(!linux.contains("{{"));
+}
+
+#[test]
+fn unknown_pipe_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Echo
+        call:
+          function: EchoArgument
+          parameters:
+            argument: '"Hello"'
+functions:
+  - name: EchoArgument
+    parameters:
+      - name: argument
+    code: echo {{ $argument | noSuchPipe }}
+"#,
+    )
+    .unwrap();
+
+    assert!(matches!(cd.parse(None, false, None), Err(ParseError::UnknownPipe(pipe)) if pipe == "noSuchPipe"));
+}
+
+#[test]
+fn misspelled_pipe_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Echo
+        call:
+          function: EchoArgument
+          parameters:
+            argument: '"Hello"'
+functions:
+  - name: EchoArgument
+    parameters:
+      - name: argument
+    code: echo "{{ $argument | escapeDoubleQuote }}"
+"#,
+    )
+    .unwrap();
+
+    assert!(matches!(cd.parse(None, false, None), Err(ParseError::UnknownPipe(pipe)) if pipe == "escapeDoubleQuote"));
+}
+
+#[test]
+fn recommend_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Standard script
+        recommend: standard
+        code: echo standard
+      - name: Strict script
+        recommend: strict
+        code: echo strict
+      - name: Unrecommended script
+        code: echo unrecommended
+"#,
+    )
+    .unwrap();
+
+    let standard = cd.parse(None, false, Some(Recommend::Standard)).unwrap();
+    assert!(standard.contains("echo standard"));
+    assert!(!standard.contains("echo strict"));
+    assert!(!standard.contains("echo unrecommended"));
+
+    let strict = cd.parse(None, false, Some(Recommend::Strict)).unwrap();
+    assert!(strict.contains("echo standard"));
+    assert!(strict.contains("echo strict"));
+    assert!(!strict.contains("echo unrecommended"));
+
+    let all = cd.parse(None, false, None).unwrap();
+    assert!(all.contains("echo standard"));
+    assert!(all.contains("echo strict"));
+    assert!(all.contains("echo unrecommended"));
+
+    assert!(Recommend::Strict.includes(Recommend::Standard));
+    assert!(Recommend::Strict.includes(Recommend::Strict));
+    assert!(Recommend::Standard.includes(Recommend::Standard));
+    assert!(!Recommend::Standard.includes(Recommend::Strict));
+}
+
+#[test]
+fn include_unrecommended_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    let count = |include_unrecommended| {
+        cd.parse_with(&ParseOptions {
+            recommend: Some(Recommend::Standard),
+            include_unrecommended,
+            ..Default::default()
+        })
+        .unwrap()
+        .matches("\necho --- ")
+        .count()
+    };
+
+    let unrecommended = cd
+        .iter_scripts()
+        .filter(|(_, script)| cd.recommend_of(&script.name) == Some(None))
+        .count();
+    assert!(unrecommended > 0);
+    assert_eq!(count(true), count(false) + unrecommended);
+}
+
+#[test]
+fn many_function_calls_test() {
+    let count = 50;
+    let scripts = (0..count)
+        .map(|i| format!("      - name: Script {i}\n        call:\n          function: Function{i}\n"))
+        .collect::<String>();
+    let functions = (0..count)
+        .rev()
+        .map(|i| format!("  - name: Function{i}\n    code: echo {i}\n"))
+        .collect::<String>();
+    let cd: CollectionData = serde_yaml::from_str(&format!(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+{scripts}functions:
+{functions}"
+    ))
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    let echoes = script
+        .lines()
+        .filter(|l| l.starts_with("echo ") && !l.starts_with("echo ---"))
+        .collect::<Vec<_>>();
+    assert_eq!(echoes, (0..count).map(|i| format!("echo {i}")).collect::<Vec<_>>());
+}
+
+#[test]
+fn duplicate_function_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Function
+functions:
+  - name: Function
+    code: echo first
+  - name: Function
+    code: echo second
+",
+    )
+    .unwrap();
+
+    assert!(matches!(cd.parse(None, false, None), Err(ParseError::DuplicateFunction(name)) if name == "Function"));
+}
+
+#[test]
+fn with_required_parameter_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Function
+          parameters:
+            argument: value
+functions:
+  - name: Function
+    parameters:
+      - name: argument
+    code: |-
+      {{ with $argument }}
+      echo {{ . }}
+      {{ end }}
+",
+    )
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert!(script.contains("\necho value\n"));
+    assert!(!script.contains("{{"));
+    assert!(!script.contains("}}"));
+}
+
+#[test]
+fn with_context_test() {
+    for (code, expected) in [
+        ("{{ . }}", "'\"value\"'"),
+        ("{{.}}", "'\"value\"'"),
+        ("{{  .  }}", "'\"value\"'"),
+        ("{{ .  | escapeDoubleQuotes }}", "'\"^\"\"value\"^\"\"'"),
+        ("{{.|escapeDoubleQuotes}}", "'\"^\"\"value\"^\"\"'"),
+    ] {
+        let cd: CollectionData = serde_yaml::from_str(&format!(
+            r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Function
+          parameters:
+            argument: '"value"'
+functions:
+  - name: Function
+    parameters:
+      - name: argument
+        optional: true
+    code: "{{{{ with $argument }}}}echo '{code}'{{{{ end }}}}"
+"#
+        ))
+        .unwrap();
+
+        let script = cd.parse(None, false, None).unwrap();
+        assert!(script.contains(&format!("\necho {expected}\n")), "{code}: {script}");
+    }
+}
+
+#[test]
+fn parse_stats_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+    let options = ParseOptions {
+        recommend: Some(Recommend::Standard),
+        ..Default::default()
+    };
+
+    let (script, stats) = cd.parse_stats(&options).unwrap();
+
+    assert_eq!(
+        stats,
+        ParseStats {
+            scripts: script.matches("\necho --- ").count(),
+            lines: script.split('\n').count(),
+            bytes: script.len(),
+        }
+    );
+    assert_eq!(stats.scripts, cd.preview(&options).unwrap().len());
+}
+
+#[test]
+fn separator_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    let separator = "\n\x0c\n";
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            separator: Some(separator.to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert!(!script.contains("\n\n\n"));
+    // start code, every script and end code
+    assert_eq!(script.split(separator).count(), cd.iter_scripts().count() + 2);
+    // skip the start code as it contains the current time
+    assert_eq!(
+        script.split_once(separator).unwrap().1.replace(separator, "\n\n\n"),
+        cd.parse_with(&ParseOptions::default())
+            .unwrap()
+            .split_once("\n\n\n")
+            .unwrap()
+            .1
+    );
+}
+
+#[test]
+fn inline_powershell_line_endings_test() {
+    let lines = [
+        "$a = @'",
+        "first 'line'",
+        "  second line",
+        "'@",
+        "# comment",
+        "#",
+        "Write-Host `",
+        "  $a <# inline #>",
+        "$b = @\"",
+        "x \"y\"",
+        "z",
+        "\"@",
+    ];
+    let inline = |code: String| {
+        let argument = code
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\r', "\\r")
+            .replace('\n', "\\n");
+        let cd: CollectionData = serde_yaml::from_str(&format!(
+            r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Inline
+        call:
+          function: Inline
+          parameters:
+            code: "{argument}"
+functions:
+  - name: Inline
+    parameters:
+      - name: code
+    code: '{{{{ $code | inlinePowerShell }}}}'
+"#
+        ))
+        .unwrap();
+        let script = cd.parse(None, false, None).unwrap();
+        script.lines().find(|l| l.starts_with("$a")).unwrap().to_string()
+    };
+
+    let expected = concat!(
+        "$a = 'first ''line'''+\"`r`n\"+'  second line'; Write-Host $a <# inline #>; ",
+        "$b = \"x `\"y`\"`r`nz\""
+    );
+    assert_eq!(inline(lines.join("\n")), expected);
+    assert_eq!(inline(lines.join("\r\n")), expected);
+    assert_eq!(inline(lines.join("\r")), expected);
+    assert_eq!(
+        inline(
+            lines
+                .iter()
+                .zip(["\n", "\r", "\r\n"].iter().cycle())
+                .map(|(line, newline)| format!("{line}{newline}"))
+                .collect()
+        ),
+        expected
+    );
+}
+
+#[test]
+fn power_shell_single_quote_test() {
+    let parse = |message: &str| {
+        serde_yaml::from_str::<CollectionData>(&format!(
+            r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Write
+        call:
+          function: WriteMessage
+          parameters:
+            message: "{message}"
+functions:
+  - name: WriteMessage
+    parameters:
+      - name: message
+    code: PowerShell -Command "Write-Host '{{{{ $message | powerShellSingleQuote }}}}'"
+"#
+        ))
+        .unwrap()
+        .parse(None, false, None)
+        .unwrap()
+    };
+
+    assert!(parse("It's 'quoted'").contains("PowerShell -Command \"Write-Host 'It''s ''quoted'''\""));
+    // Curly quotes end single quoted strings too
+    assert!(parse("x’; Remove-Item C:\\\\ -Recurse; ‘‚‛")
+        .contains("PowerShell -Command \"Write-Host 'x’’; Remove-Item C:\\ -Recurse; ‘‘‚‚‛‛'\""));
+}
+
+#[test]
+fn echo_command_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: windows
+scripting:
+  language: powershell
+  fileExtension: ps1
+  startCode: start
+  endCode: end
+  echoCommand: Write-Host ---
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: Get-Process
+",
+    )
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert!(script.contains("\nWrite-Host --- Script\nGet-Process\n"));
+    assert!(!script.contains("echo ---"));
+}
+
+#[test]
+fn parse_one_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+
+    let script = cd.parse_one("Clear bash history", false).unwrap();
+    assert!(script.starts_with("# ----"));
+    assert!(script.contains("\necho --- Clear bash history\nrm -fv ~/.bash_history\n"));
+    assert!(!script.contains("Clear Zsh history"));
+    assert!(!script.contains("#!/usr/bin/env bash"));
+    assert!(cd.parse(None, false, None).unwrap().contains(&script));
+
+    assert!(matches!(
+        cd.parse_one("No such script", false),
+        Err(ParseError::UnknownNames(names)) if names == ["No such script"]
+    ));
+}
+
+#[test]
+fn include_wrapper_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+
+    let wrapped = cd.parse_with(&ParseOptions::default()).unwrap();
+    let unwrapped = cd
+        .parse_with(&ParseOptions {
+            include_wrapper: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert!(unwrapped.starts_with("# ----"));
+    assert!(unwrapped.ends_with("# ------------------------------------------------------------"));
+    assert!(wrapped.contains(&unwrapped));
+    // start code with the current time and end code, each followed or preceded by the separator
+    let (start, rest) = wrapped.split_once("\n\n\n").unwrap();
+    let (_, end) = rest.rsplit_once("\n\n\n").unwrap();
+    assert_eq!(wrapped.len(), start.len() + unwrapped.len() + end.len() + 6);
+}
+
+#[test]
+fn env_test() {
+    let collection = |variable: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            r#"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Greet
+        call:
+          function: Greet
+          parameters:
+            greeting: Hello
+            name: '{{{{ env "{variable}" }}}}'
+functions:
+  - name: Greet
+    parameters:
+      - name: greeting
+      - name: name
+    code: echo {{{{ $greeting }}}} {{{{ env "{variable}" }}}} / {{{{ $name }}}}
+"#
+        ))
+        .unwrap()
+    };
+
+    let variable = format!("PRIVACY_SEXY_ENV_TEST_{}", std::process::id());
+    std::env::set_var(&variable, "World");
+    assert!(collection(&variable)
+        .parse(None, false, None)
+        .unwrap()
+        .contains("\necho Hello World / World\n"));
+
+    let missing = format!("{variable}_MISSING");
+    assert!(matches!(
+        collection(&missing).parse(None, false, None),
+        Err(ParseError::EnvVar(name)) if name == missing
+    ));
+}
+
+#[test]
+fn normalize_line_endings_test() {
+    use privacy_sexy::{normalize_line_endings, OS};
+
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: windows
+scripting:
+  language: batchfile
+  startCode: \"@echo off\\r\\nsetlocal\"
+  endCode: \"endlocal\\rexit /b 0\"
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: \"echo one\\r\\necho two\\recho three\\necho four\"
+",
+    )
+    .unwrap();
+
+    let script = normalize_line_endings(&cd.parse(None, false, None).unwrap(), cd.os);
+    assert_eq!(script.matches("\r\n").count(), script.matches('\n').count());
+    assert_eq!(script.matches("\r\n").count(), script.matches('\r').count());
+    assert!(script.contains("\r\necho one\r\necho two\r\necho three\r\necho four\r\n"));
+    assert!(script.ends_with("endlocal\r\nexit /b 0"));
+
+    assert_eq!(normalize_line_endings(&script, OS::Linux), script.replace("\r\n", "\n"));
+}
+
+#[test]
+fn parse_segments_test() {
+    let cd = CollectionData::from_file("collections/windows.yaml").unwrap();
+    let options = ParseOptions {
+        recommend: Some(Recommend::Standard),
+        ..Default::default()
+    };
+
+    let segments = cd.parse_segments(&options).unwrap();
+    let selected = cd.preview(&options).unwrap();
+
+    assert_eq!(segments.len(), selected.len());
+    for ((name, code), script) in segments.iter().zip(selected) {
+        assert_eq!(name, &script.name);
+        assert!(code.contains(&format!("\necho --- {name}\n")));
+    }
+}
+
+#[test]
+fn raw_test() {
+    for (os, language) in [("linux", "shellscript"), ("windows", "batchfile")] {
+        let cd: CollectionData = serde_yaml::from_str(&format!(
+            "
+os: {os}
+scripting:
+  language: {language}
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: First
+        code: echo first
+      - name: Second
+        call:
+          function: Second
+functions:
+  - name: Second
+    code: echo second
+"
+        ))
+        .unwrap();
+
+        let script = cd
+            .parse_with(&ParseOptions {
+                raw: true,
+                category_headers: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(script, "start\n\n\necho first\n\n\necho second\n\n\nend");
+        assert!(!script.lines().any(|l| l.starts_with('#') || l.starts_with("::")));
+    }
+}
+
+#[test]
+fn sudo_test() {
+    let check = "if [ \"$(id -u)\" -ne 0 ]; then\n  exec sudo \"$0\" \"$@\"\nfi";
+    let options = ParseOptions {
+        sudo: true,
+        include_wrapper: false,
+        ..Default::default()
+    };
+
+    for os in ["linux", "macos", "windows"] {
+        let cd: CollectionData = serde_yaml::from_str(&format!(
+            "
+os: {os}
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo script
+"
+        ))
+        .unwrap();
+
+        let script = cd.parse_with(&options).unwrap();
+        assert_eq!(script.starts_with(&format!("{check}\n\n\n")), os == "linux");
+        assert_eq!(script.matches(check).count(), usize::from(os == "linux"));
+    }
+}
+
+#[test]
+fn scripting_default_test() {
+    use privacy_sexy::{
+        collection::{CategoryData, CategoryOrScriptData, ScriptData, ScriptingDefinitionData},
+        OS,
+    };
+
+    let cd = CollectionData {
+        os: OS::Linux,
+        title: None,
+        description: None,
+        schema_version: None,
+        scripting: ScriptingDefinitionData {
+            language: "shellscript".to_string(),
+            ..Default::default()
+        },
+        actions: vec![CategoryData {
+            children: vec![CategoryOrScriptData::ScriptData(ScriptData {
+                name: "Script".to_string(),
+                code: Some("echo script".to_string()),
+                revert_code: None,
+                check_code: None,
+                priority: 0,
+                revert_only: false,
+                requires: None,
+                call: None,
+                docs: None,
+                recommend: None,
+            })],
+            category: "Category".to_string(),
+            docs: None,
+            recommend: None,
+        }],
+        functions: None,
+        imports: None,
+    };
+
+    assert_eq!(
+        cd.parse_with(&ParseOptions {
+            raw: true,
+            ..Default::default()
+        })
+        .unwrap(),
+        "\n\n\necho script\n\n\n"
+    );
+    assert!(cd
+        .parse(None, false, None)
+        .unwrap()
+        .contains("\necho --- Script\necho script\n"));
+}
+
+#[test]
+fn adversarial_parameters_test() {
+    use serde_yaml::Value;
+
+    const FRAGMENTS: [&str; 24] = [
+        "a",
+        " ",
+        "\n",
+        "\r",
+        "\"",
+        "'",
+        "\\",
+        "$",
+        "{{",
+        "}}",
+        "{",
+        "}",
+        "|",
+        ".",
+        "$a",
+        "$b",
+        "with",
+        "end",
+        "if os == \"linux\"",
+        "env \"PATH\"",
+        "escapeDoubleQuotes",
+        "inlinePowerShell",
+        "@'",
+        "(?",
+    ];
+
+    // xorshift64, deterministic so that failures are reproducible
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+    let mut value = || -> Value {
+        match next(6) {
+            0 => Value::Null,
+            1 => Value::Bool(next(2) == 0),
+            2 => Value::Number((next(2000) as i64 - 1000).into()),
+            3 => Value::String((0..next(12)).map(|_| FRAGMENTS[next(FRAGMENTS.len())]).collect()),
+            4 => Value::Sequence(vec![Value::String("a".into())]),
+            _ => serde_yaml::from_str("{ a: b }").unwrap(),
+        }
+    };
+
+    let base: Value = serde_yaml::from_str(
+        r#"
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Function
+functions:
+  - name: Function
+    parameters:
+      - name: a
+      - name: b
+        optional: true
+    code: |-
+      echo {{ $a }} {{ $a | escapeDoubleQuotes | inlinePowerShell }}
+      {{ with $b }}echo {{ . }} {{ . | inlinePowerShell }}{{ end }}
+      {{ if os == "linux" }}echo {{ $a }}{{ end }}
+"#,
+    )
+    .unwrap();
+
+    for _ in 0..300 {
+        let mut parameters = serde_yaml::Mapping::new();
+        parameters.insert("a".into(), value());
+        parameters.insert("b".into(), value());
+
+        let mut collection = base.clone();
+        collection["actions"][0]["children"][0]["call"]["parameters"] = Value::Mapping(parameters);
+        let cd = CollectionData::try_from(serde_yaml::to_string(&collection).unwrap().as_str()).unwrap();
+
+        // Errors are fine, panics are not
+        let _ = cd.parse(None, false, None);
+    }
+}
+
+#[test]
+fn null_parameter_test() {
+    let collection = |parameters: &str| {
+        CollectionData::try_from(
+            format!(
+                "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Function
+          parameters:
+{parameters}
+functions:
+  - name: Function
+    parameters:
+      - name: a
+      - name: b
+        optional: true
+    code: echo {{{{ $a }}}}{{{{ with $b }}}}{{{{ . }}}}{{{{ end }}}}
+"
+            )
+            .as_str(),
+        )
+        .unwrap()
+    };
+
+    let script = collection("            a: x\n            b:")
+        .parse_one("Script", false)
+        .unwrap();
+    assert!(script.contains("\necho x\n"));
+    assert_eq!(
+        script,
+        collection("            a: x").parse_one("Script", false).unwrap()
+    );
+    assert!(collection("            a: x\n            b: y")
+        .parse_one("Script", false)
+        .unwrap()
+        .contains("\necho xy\n"));
+    assert!(collection("            a:\n            b: y")
+        .parse_one("Script", false)
+        .is_err());
+}
+
+#[test]
+fn strip_comments_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: |-
+          #!/bin/sh
+          # Explanation
+            # Indented explanation
+          :: Batch explanation
+          REM Remark
+          rem
+          echo kept # trailing comment
+          REMOVE-Item kept
+",
+    )
+    .unwrap();
+
+    let stripped = cd
+        .parse_with(&ParseOptions {
+            strip_comments: true,
+            raw: true,
+            include_wrapper: false,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(stripped, "#!/bin/sh\necho kept # trailing comment\nREMOVE-Item kept");
+
+    let beautified = cd
+        .parse_with(&ParseOptions {
+            strip_comments: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(beautified.contains(":: ----"));
+    assert!(!beautified.contains("explanation"));
+}
+
+#[test]
+fn strip_powershell_comments_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: windows
+scripting:
+  language: powershell
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: |-
+          #Requires -RunAsAdministrator
+          # Explanation
+          <#
+            Block explanation
+            # Nested line
+          #>
+          Write-Host first
+          <# Inline block #> Write-Host second
+          Write-Host third # trailing comment
+",
+    )
+    .unwrap();
+
+    let stripped = cd
+        .parse_with(&ParseOptions {
+            strip_comments: true,
+            raw: true,
+            include_wrapper: false,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(
+        stripped,
+        "#Requires -RunAsAdministrator\nWrite-Host first\nWrite-Host second\nWrite-Host third # trailing comment"
+    );
+}
+
+#[test]
+fn parse_collect_errors_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Good
+        code: echo good
+      - name: Unknown function
+        call:
+          function: NoSuchFunction
+      - name: Missing parameter
+        call:
+          function: Echo
+  - category: Other category
+    children:
+      - name: Also good
+        call:
+          function: Echo
+          parameters:
+            text: also good
+functions:
+  - name: Echo
+    parameters:
+      - name: text
+    code: echo {{ $text }}
+",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        cd.parse_with(&ParseOptions::default()),
+        Err(ParseError::Function(name)) if name == "NoSuchFunction"
+    ));
+
+    let (script, errors) = cd.parse_collect_errors(&ParseOptions::default());
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(&errors[0], ParseError::Function(name) if name == "NoSuchFunction"));
+    assert!(matches!(&errors[1], ParseError::Parameter(name) if name == "text"));
+    assert!(script.contains("\necho good\n"));
+    assert!(script.contains("\necho also good\n"));
+    assert!(!script.contains("Unknown function"));
+    assert!(!script.contains("Missing parameter"));
+}
+
+#[test]
+fn parameter_default_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Omitted
+        call:
+          function: Greet
+      - name: Provided
+        call:
+          function: Greet
+          parameters:
+            name: Everyone
+functions:
+  - name: Greet
+    parameters:
+      - name: name
+        default: World
+    code: echo Hello {{ $name }}
+",
+    )
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert!(script.contains("\necho Hello World\n"));
+    assert!(script.contains("\necho Hello Everyone\n"));
+}
+
+#[test]
+fn yaml_anchors_test() {
+    let expanded: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: Test Start Code
+  endCode: Test End Code
+actions:
+  - category: Test Cat 1
+    children:
+      - name: Action 1
+        code: rm -rf ~/.cache/thumbnails
+        revertCode: echo 'Nothing to revert'
+      - name: Action 2
+        call:
+          function: func1
+          parameters:
+            var1: test
+  - category: Test Cat 2
+    children:
+      - name: Action 3
+        code: rm -rf ~/.cache/thumbnails
+        revertCode: echo 'Nothing to revert'
+      - name: Action 4
+        call:
+          - function: func1
+            parameters:
+              var1: test
+          - function: func2
+            parameters:
+              var1: test
+functions:
+  - name: func1
+    parameters:
+      - name: var1
+    code: echo {{ $var1 }}
+  - name: func2
+    parameters:
+      - name: var1
+    code: echo {{ $var1 }}
+",
+    )
+    .unwrap();
+    let anchored = CollectionData::from_file("tests/collections/anchors.yml").unwrap();
+
+    assert!(anchored.validate().is_ok());
+    for revert in [false, true] {
+        let names = revert.then(|| vec!["Action 1", "Action 3"]);
+        assert_eq!(
+            anchored.parse(names.as_ref(), revert, None).unwrap(),
+            expanded.parse(names.as_ref(), revert, None).unwrap()
+        );
+    }
+}
+
+#[test]
+fn require_docs_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Documented
+        code: echo documented
+        docs: https://example.com
+      - name: Undocumented
+        code: echo undocumented
+",
+    )
+    .unwrap();
+
+    let options = |names: &[&str]| ParseOptions {
+        names: Some(names.iter().map(ToString::to_string).collect()),
+        require_docs: true,
+        ..Default::default()
+    };
+
+    assert!(cd.parse_with(&options(&["Documented"])).is_ok());
+    assert!(matches!(
+        cd.parse_with(&options(&["Documented", "Undocumented"])),
+        Err(ParseError::MissingDocs(name)) if name == "Undocumented"
+    ));
+    assert!(cd.parse(None, false, None).is_ok());
+}
+
+#[test]
+fn write_parsed_test() {
+    let cd = CollectionData::from_file("collections/windows.yaml").unwrap();
+
+    for options in [
+        ParseOptions {
+            include_wrapper: false,
+            ..Default::default()
+        },
+        ParseOptions {
+            include_wrapper: false,
+            category_headers: true,
+            recommend: Some(Recommend::Standard),
+            ..Default::default()
+        },
+        ParseOptions {
+            include_wrapper: false,
+            names: Some(vec!["No such script".to_string()]),
+            ..Default::default()
+        },
+    ] {
+        let mut written = Vec::new();
+        cd.write_parsed(&mut written, &options).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), cd.parse_with(&options).unwrap());
+    }
+}
+
+#[test]
+fn category_recommend_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Strict category
+    recommend: strict
+    children:
+      - name: Inherited
+        code: echo inherited
+      - name: Overridden
+        recommend: standard
+        code: echo overridden
+      - category: Nested category
+        children:
+          - name: Nested
+            code: echo nested
+  - category: Unrecommended category
+    children:
+      - name: Unrecommended
+        code: echo unrecommended
+",
+    )
+    .unwrap();
+
+    let names = |recommend| {
+        cd.preview(&ParseOptions {
+            recommend: Some(recommend),
+            ..Default::default()
+        })
+        .unwrap()
+        .into_iter()
+        .map(|script| script.name.as_str())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(names(Recommend::Strict), ["Inherited", "Overridden", "Nested"]);
+    assert_eq!(names(Recommend::Standard), ["Overridden"]);
+
+    assert!(matches!(cd.recommend_of("Nested"), Some(Some(Recommend::Strict))));
+    assert!(matches!(cd.recommend_of("Overridden"), Some(Some(Recommend::Standard))));
+    assert!(matches!(cd.recommend_of("Unrecommended"), Some(None)));
+    assert!(cd.has_recommend(Recommend::Strict));
+}
+
+#[test]
+fn check_code_test() {
+    let collection = |os: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            "
+os: {os}
+scripting:
+  language: script
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Checked script
+        checkCode: check
+        code: apply
+        revertCode: revert
+      - name: Unchecked script
+        code: unchecked
+      - name: Checked-script
+        checkCode: check2
+        code: '# comment'
+"
+        ))
+        .unwrap()
+    };
+
+    let linux = collection("linux").parse(None, false, None).unwrap();
+    assert!(
+        linux.contains("\nif ( check ) >/dev/null 2>&1; then\n  echo 'Skipping, already applied'\nelse\napply\nfi\n")
+    );
+    assert!(linux.contains("\necho --- Unchecked script\nunchecked\n"));
+
+    let windows = collection("windows").parse(None, false, None).unwrap();
+    assert!(windows.contains(
+        "\n(check) >nul 2>&1 && echo Skipping, already applied && goto :skip_checked_script_0\napply\n:skip_checked_script_0\n"
+    ));
+    assert!(windows.contains("goto :skip_checked_script_2\n# comment\n:skip_checked_script_2\n"));
+
+    let mut powershell = collection("windows");
+    powershell.scripting.language = "powershell".to_string();
+    assert!(powershell
+        .parse(None, false, None)
+        .unwrap()
+        .contains("\ncheck *> $null\nif ($?) {\n  Write-Host 'Skipping, already applied'\n} else {\napply\n}\n"));
+
+    let stripped = collection("linux")
+        .parse_with(&ParseOptions {
+            strip_comments: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(
+        stripped.contains("\nif ( check2 ) >/dev/null 2>&1; then\n  echo 'Skipping, already applied'\nelse\n:\nfi\n")
+    );
+
+    let names = vec!["Checked script"];
+    let reverted = collection("linux").parse(Some(&names), true, None).unwrap();
+    assert!(reverted.contains("\nrevert\n"));
+    assert!(!reverted.contains("check"));
+}
+
+#[test]
+fn list_parameter_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Uninstall apps
+        call:
+          function: UninstallApp
+          parameters:
+            appName:
+              - Microsoft.BingNews
+              - Microsoft.BingWeather
+              - Microsoft.WindowsFeedbackHub
+            scope: -AllUsers
+functions:
+  - name: UninstallApp
+    parameters:
+      - name: appName
+      - name: scope
+    code: PowerShell -Command \"Get-AppxPackage {{ $scope }} '{{ $appName }}' | Remove-AppxPackage\"
+",
+    )
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert!(script.contains(
+        "
+PowerShell -Command \"Get-AppxPackage -AllUsers 'Microsoft.BingNews' | Remove-AppxPackage\"
+PowerShell -Command \"Get-AppxPackage -AllUsers 'Microsoft.BingWeather' | Remove-AppxPackage\"
+PowerShell -Command \"Get-AppxPackage -AllUsers 'Microsoft.WindowsFeedbackHub' | Remove-AppxPackage\"
+"
+    ));
+}
+
+#[test]
+fn reg_escape_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Set value
+        call:
+          function: SetRegistryValue
+          parameters:
+            keyPath: HKLM\SOFTWARE\Policies\Microsoft\Windows\
+            data: 'C:\Program Files\App "%VERSION%"'
+functions:
+  - name: SetRegistryValue
+    parameters:
+      - name: keyPath
+      - name: data
+    code: reg add "{{ $keyPath | regEscape }}" /v "Path" /t REG_SZ /d "{{ $data | regEscape }}" /f
+"#,
+    )
+    .unwrap();
+
+    assert!(cd.parse(None, false, None).unwrap().contains(
+        r#"reg add "HKLM\SOFTWARE\Policies\Microsoft\Windows\\" /v "Path" /t REG_SZ /d "C:\Program Files\App \"%%VERSION%%\"" /f"#
+    ));
+}
+
+#[test]
+fn priority_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Cleanup
+    children:
+      - name: Clean
+        code: echo clean
+      - name: Clean later
+        priority: -1
+        code: echo later
+  - category: Safety
+    children:
+      - name: Create restore point
+        priority: 10
+        code: echo restore
+      - name: Tweak
+        code: echo tweak
+",
+    )
+    .unwrap();
+
+    let names = cd
+        .preview(&ParseOptions::default())
+        .unwrap()
+        .into_iter()
+        .map(|script| script.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Create restore point", "Clean", "Tweak", "Clean later"]);
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            category_headers: true,
+            ..Default::default()
+        })
+        .unwrap();
+    let position = |text: &str| script.find(text).unwrap();
+    assert!(position("echo restore") < position("echo clean"));
+    assert!(position("echo tweak") < position("echo later"));
+    assert_eq!(script.matches("=Safety=").count(), 2);
+    assert_eq!(script.matches("=Cleanup=").count(), 2);
+}
+
+#[test]
+fn requires_test() {
+    let collection = |requires_b: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: B
+        requires: [A]
+        code: echo b
+        revertCode: undo b
+      - name: C
+        code: echo c
+        revertCode: undo c
+  - category: Prerequisites
+    children:
+      - name: A
+        requires: {requires_b}
+        code: echo a
+        revertCode: undo a
+"
+        ))
+        .unwrap()
+    };
+
+    let cd = collection("[]");
+    let options = ParseOptions {
+        names: Some(vec!["B".to_string()]),
+        ..Default::default()
+    };
+    let names = cd
+        .preview(&options)
+        .unwrap()
+        .into_iter()
+        .map(|script| script.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["A", "B"]);
+
+    let script = cd.parse_with(&options).unwrap();
+    assert!(script.find("echo a").unwrap() < script.find("echo b").unwrap());
+    assert!(!script.contains("echo c"));
+
+    let reverted = ParseOptions {
+        names: Some(vec!["B".to_string(), "C".to_string()]),
+        revert: true,
+        ..Default::default()
+    };
+    let names = cd
+        .preview(&reverted)
+        .unwrap()
+        .into_iter()
+        .map(|script| script.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["B", "A", "C"]);
+    let script = cd.parse_with(&reverted).unwrap();
+    assert!(script.find("undo b").unwrap() < script.find("undo a").unwrap());
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert_eq!(script.matches("echo a").count(), 1);
+
+    assert!(matches!(
+        collection("[B]").parse_with(&options),
+        Err(ParseError::DependencyCycle(cycle)) if cycle == ["B", "A", "B"]
+    ));
+    assert!(matches!(
+        collection("[D]").parse_with(&options),
+        Err(ParseError::UnknownNames(names)) if names == ["D"]
+    ));
+}
+
+#[test]
+fn to_function_library_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    let options = ParseOptions {
+        recommend: Some(Recommend::Strict),
+        ..Default::default()
+    };
+
+    let library = cd.to_function_library(&options).unwrap();
+    let functions = library
+        .lines()
+        .filter_map(|line| line.strip_prefix("tweak_")?.strip_suffix("() {"))
+        .collect::<Vec<_>>();
+    assert_eq!(functions.len(), cd.preview(&options).unwrap().len());
+    assert!(functions.iter().all(|name| name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')));
+    assert!(library.contains("tweak_clear_bash_history() {\n"));
+
+    let windows = CollectionData::from_file("collections/windows.yaml").unwrap();
+    let library = windows
+        .to_function_library(&ParseOptions {
+            names: Some(vec!["Clear Listary indexes".to_string()]),
+            raw: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(library.starts_with("goto :eof\n\n:tweak_clear_listary_indexes\n"));
+    assert!(library.ends_with("\nexit /b"));
+
+    let mut powershell = windows;
+    powershell.scripting.language = "powershell".to_string();
+    let library = powershell
+        .to_function_library(&ParseOptions {
+            names: Some(vec!["Clear Listary indexes".to_string()]),
+            raw: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(library.starts_with("function tweak_clear_listary_indexes {\n"));
+    assert!(library.ends_with("\n}"));
+    assert!(!library.contains("exit /b"));
+}
+
+#[test]
+fn parse_excluding_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Safe script
+        code: echo safe
+      - name: Disable format check
+        code: echo check
+      - name: Wipe script
+        call:
+          function: Wipe
+          parameters:
+            path: /
+functions:
+  - name: Wipe
+    parameters:
+      - name: path
+    code: rm -rf {{ $path }}
+",
+    )
+    .unwrap();
+
+    let mut omitted = Vec::new();
+    let script = cd
+        .parse_excluding(&ParseOptions::default(), &["rm -rf /", "format"], &mut omitted)
+        .unwrap();
+    assert_eq!(omitted, ["Wipe script"]);
+    assert!(script.contains("echo safe"));
+    assert!(script.contains("echo check"));
+    assert!(!script.contains("rm -rf"));
+
+    let mut omitted = Vec::new();
+    let script = cd.parse_excluding(&ParseOptions::default(), &[], &mut omitted).unwrap();
+    assert!(omitted.is_empty());
+    assert_eq!(script, cd.parse(None, false, None).unwrap());
+}
+
+#[test]
+fn bom_test() {
+    let options = ParseOptions {
+        bom: true,
+        ..Default::default()
+    };
+
+    let cd = CollectionData::from_file("collections/windows.yaml").unwrap();
+    let mut bytes = Vec::new();
+    cd.write_parsed(&mut bytes, &options).unwrap();
+    assert!(bytes.starts_with(&[0xEF, 0xBB, 0xBF]));
+    assert!(bytes[3..].starts_with(b"@echo off"));
+
+    let mut bytes = Vec::new();
+    cd.write_parsed(&mut bytes, &ParseOptions::default()).unwrap();
+    assert!(!bytes.starts_with(&[0xEF, 0xBB, 0xBF]));
+
+    let names = Some(vec!["Clear Listary indexes".to_string()]);
+    let each = cd
+        .parse_each(&ParseOptions {
+            names: names.clone(),
+            ..options.clone()
+        })
+        .unwrap();
+    assert!(each[0].1.starts_with("\u{feff}@echo off"));
+    let each = cd
+        .parse_each(&ParseOptions {
+            names,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(each[0].1.starts_with("@echo off"));
+
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    assert!(!cd.parse_with(&options).unwrap().starts_with('\u{feff}'));
+}
+
+#[test]
+fn raw_code_test() {
+    let cd = CollectionData::from_file("collections/macos.yaml").unwrap();
+
+    let script = cd.find_script("Clear bash history").unwrap();
+    let raw = script.raw_code(false).unwrap();
+    assert_eq!(raw, "rm -f ~/.bash_history");
+    let parsed = cd.parse_one("Clear bash history", false).unwrap();
+    assert_ne!(parsed, raw);
+    assert!(parsed.contains(raw));
+    assert!(script.raw_code(true).is_none());
+
+    let (_, caller) = cd.iter_scripts().find(|(_, script)| script.call.is_some()).unwrap();
+    assert!(caller.raw_code(false).is_none());
+}
+
+#[test]
+fn revert_only_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Enable firewall
+        revertOnly: true
+        code: ufw disable
+        revertCode: ufw enable
+      - name: Enable service
+        revertOnly: true
+        call:
+          function: ToggleService
+          parameters:
+            service: cups
+functions:
+  - name: ToggleService
+    parameters:
+      - name: service
+    code: systemctl disable {{ $service }}
+    revertCode: systemctl enable {{ $service }}
+",
+    )
+    .unwrap();
+
+    let script = cd.parse(None, false, None).unwrap();
+    assert!(script.contains("\nufw enable\n"));
+    assert!(script.contains("\nsystemctl enable cups\n"));
+    assert!(!script.contains("disable"));
+
+    let script = cd.parse(None, true, None).unwrap();
+    assert!(script.contains("\nufw disable\n"));
+    assert!(script.contains("\nsystemctl disable cups\n"));
+    assert!(!script.contains(" enable"));
+
+    assert_eq!(cd.list_revertable(), ["Enable firewall", "Enable service"]);
+}
+
+#[test]
+fn colorize_test() {
+    use privacy_sexy::renderer::{BashRenderer, BatchRenderer, ScriptRenderer};
+
+    let script = "#!/usr/bin/env bash\n# ----------------------------------------------------------\n# ----------------Clear bash history------------------------\n# ----------------------------------------------------------\necho --- Clear bash history\nrm -f ~/.bash_history # comment\n\n# Note";
+    assert_eq!(
+        BashRenderer.colorize(script),
+        "#!/usr/bin/env bash\n\x1b[36m# ----------------------------------------------------------\x1b[0m\n\x1b[1;33m# ----------------Clear bash history------------------------\x1b[0m\n\x1b[36m# ----------------------------------------------------------\x1b[0m\necho --- Clear bash history\nrm -f ~/.bash_history # comment\n\n\x1b[36m# Note\x1b[0m"
+    );
+
+    let plain = "@echo off\nreg add HKLM\\Software /f\necho --- Done";
+    assert_eq!(BatchRenderer.colorize(plain), plain);
+    assert_eq!(
+        BatchRenderer.colorize(":: Note\nexit /b"),
+        "\x1b[36m:: Note\x1b[0m\nexit /b"
+    );
+}
+
+#[test]
+fn renderer_test() {
+    use privacy_sexy::{
+        collection::ScriptingDefinitionData,
+        renderer::{self, ScriptRenderer},
+    };
+
+    struct Lua;
+
+    impl ScriptRenderer for Lua {
+        fn comment_line(&self, text: &str) -> String {
+            format!("-- {text}")
+        }
+
+        fn echo(&self, text: &str) -> String {
+            format!("print('--- {text}')")
+        }
+    }
+
+    renderer::register_renderer("lua-test", Lua);
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: lua-test
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: os.remove('file')
+",
+    )
+    .unwrap();
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            category_headers: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(script.contains(&format!("-- {}\n", "=".repeat(60))));
+    assert!(script.contains("\n-- ---------------------------Script---------------------------\n"));
+    assert!(script.contains("\nprint('--- Script')\nos.remove('file')\n"));
+    assert!(!script.contains("# -"));
+
+    let powershell = CollectionData {
+        scripting: ScriptingDefinitionData {
+            language: "powershell".to_string(),
+            ..cd.scripting.clone()
+        },
+        ..cd
+    };
+    let script = powershell.parse(None, false, None).unwrap();
+    assert!(script.contains("\n# ---------------------------Script---------------------------\n"));
+    assert!(script.contains("\nWrite-Host --- Script\n"));
+}
+
+#[test]
+fn multi_line_parameter_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        r#"
+os: windows
+scripting:
+  language: batchfile
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Plain
+        call:
+          function: Echo
+          parameters:
+            text: |-
+              first $1 line
+              second {{ line }}
+              third "line"
+      - name: Inline
+        call:
+          function: RunPowerShell
+          parameters:
+            code: |-
+              $a = 1 # one
+              $b = 2
+              Write-Host "$a $b"
+functions:
+  - name: Echo
+    parameters:
+      - name: text
+    code: |-
+      before
+      {{ $text }}
+      after
+      {{ with $text }}
+      with {{ . }}
+      {{ end }}
+  - name: RunPowerShell
+    parameters:
+      - name: code
+    code: PowerShell -Command "{{ $code | inlinePowerShell | escapeDoubleQuotes }}"
+"#,
+    )
+    .unwrap();
+
+    let script = cd.parse_one("Plain", false).unwrap();
+    assert!(script.contains(
+        "\nbefore\nfirst $1 line\nsecond {{ line }}\nthird \"line\"\nafter\nwith first $1 line\nsecond {{ line }}\nthird \"line\"\n"
+    ));
+
+    let script = cd.parse_one("Inline", false).unwrap();
+    assert!(script.contains("\nPowerShell -Command \"$a = 1; $b = 2; Write-Host \"^\"\"$a $b\"^\"\"\"\n"));
+}
+
+#[test]
+fn os_name_test() {
+    let collection = |os: &str| -> CollectionData {
+        serde_yaml::from_str(&format!(
+            "
+os: {os}
+scripting:
+  language: shellscript
+  startCode: 'echo Generated for {{{{ $osName }}}}'
+  endCode: 'echo Done on {{{{ $osName }}}}'
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo '{{{{ $osName }}}}'
+"
+        ))
+        .unwrap()
+    };
+
+    let script = collection("windows").parse(None, false, None).unwrap();
+    assert!(script.starts_with("echo Generated for windows\n"));
+    assert!(script.ends_with("\necho Done on windows"));
+    // Only start and end code are substituted
+    assert!(script.contains("\necho '{{ $osName }}'\n"));
+
+    let script = collection("macos").parse(None, false, None).unwrap();
+    assert!(script.starts_with("echo Generated for macos\n"));
+}
+
+#[test]
+fn sort_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Second category
+    recommend: standard
+    children:
+      - name: charlie
+        code: echo charlie
+      - name: Alpha
+        recommend: strict
+        code: echo alpha
+  - category: First category
+    children:
+      - name: delta
+        code: echo delta
+      - name: Bravo
+        priority: 1
+        code: echo bravo
+",
+    )
+    .unwrap();
+
+    let names = |sort| {
+        cd.preview(&ParseOptions {
+            sort,
+            ..Default::default()
+        })
+        .unwrap()
+        .into_iter()
+        .map(|script| script.name.as_str())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(SortOrder::Document), ["Bravo", "charlie", "Alpha", "delta"]);
+    assert_eq!(names(SortOrder::Alphabetical), ["Bravo", "Alpha", "charlie", "delta"]);
+    assert_eq!(names(SortOrder::Recommend), ["Bravo", "Alpha", "charlie", "delta"]);
+
+    let script = cd
+        .parse_with(&ParseOptions {
+            sort: SortOrder::Alphabetical,
+            raw: true,
+            include_wrapper: false,
+            separator: Some("\n".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(script, "echo bravo\necho alpha\necho charlie\necho delta");
+}
+
+#[test]
+fn fixed_date_test() {
+    let cd = CollectionData::from_file("collections/linux.yaml").unwrap();
+    let options = ParseOptions {
+        date: Some("Thu, 1 Jan 1970 00:00:00 +0000".to_string()),
+        ..Default::default()
+    };
+
+    let script = cd.parse_with(&options).unwrap();
+    assert!(script
+        .lines()
+        .nth(1)
+        .unwrap()
+        .ends_with(" — Thu, 1 Jan 1970 00:00:00 +0000"));
+    assert!(!script.contains("{{ $date }}"));
+    assert_eq!(script, cd.parse_with(&options).unwrap());
+}
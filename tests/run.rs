@@ -0,0 +1,157 @@
+#[cfg(target_family = "unix")]
+#[test]
+fn run_script_timeout_test() {
+    use std::{
+        env, fs,
+        io::ErrorKind,
+        process::Command,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use privacy_sexy::run_script_timeout;
+
+    let start = Instant::now();
+    let err = run_script_timeout("#!/bin/sh\nsleep 5", Some("sh".into()), Duration::from_millis(200)).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    assert!(run_script_timeout("#!/bin/sh\nexit 0", None, Duration::from_secs(5))
+        .unwrap()
+        .success());
+
+    // Processes started by the script are killed too
+    let pid_file = env::temp_dir().join(format!("privacy-sexy-timeout-test-{}", std::process::id()));
+    let script = format!("#!/bin/sh\nsleep 30 &\necho $! > '{}'\nwait", pid_file.display());
+    let err = run_script_timeout(&script, None, Duration::from_millis(500)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    let pid = fs::read_to_string(&pid_file).unwrap();
+    fs::remove_file(&pid_file).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    // Killed processes may linger as zombies until reaped by init
+    let state = Command::new("ps")
+        .args(["-o", "stat=", "-p", pid.trim()])
+        .output()
+        .unwrap()
+        .stdout;
+    assert!(state.is_empty() || state.starts_with(b"Z"));
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn run_script_with_test() {
+    use std::{env, fs, path::Path};
+
+    use privacy_sexy::{run_script, run_script_with};
+
+    let script = "[ -n \"$BASH_VERSION\" ] && exit 7\nexit 1";
+
+    // Interpreter is derived from the extension
+    assert_eq!(run_script(script, Some("sh".into())).unwrap().code(), Some(7));
+
+    assert_eq!(
+        run_script_with(script, Some("sh".into()), &["bash"]).unwrap().code(),
+        Some(7)
+    );
+    assert_eq!(
+        run_script_with(script, None, &["sh", "-c", "exec bash \"$0\""])
+            .unwrap()
+            .code(),
+        Some(7)
+    );
+
+    let log = env::temp_dir().join(format!("privacy-sexy-run-with-test-{}", std::process::id()));
+    let script = format!("echo \"$0\" > '{}'", log.display());
+    assert!(run_script_with(&script, Some("sh".into()), &["sh"]).unwrap().success());
+    let tmp_file = fs::read_to_string(&log).unwrap();
+    fs::remove_file(&log).unwrap();
+    assert!(!Path::new(tmp_file.trim()).exists());
+}
+
+#[test]
+fn default_interpreter_test() {
+    use privacy_sexy::collection::CollectionData;
+
+    let interpreter = |os| {
+        CollectionData::from_file(format!("collections/{os}.yaml"))
+            .unwrap()
+            .scripting
+            .default_interpreter()
+    };
+
+    assert_eq!(interpreter("linux"), ["bash"]);
+    assert_eq!(interpreter("macos"), ["bash"]);
+    assert_eq!(interpreter("windows"), ["cmd", "/c"]);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn run_each_test() {
+    use privacy_sexy::collection::{CollectionData, ParseOptions};
+
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  fileExtension: sh
+  startCode: '#!/bin/sh'
+  endCode: exit 0
+actions:
+  - category: Category
+    children:
+      - name: Pass
+        code: 'true'
+      - name: Fail
+        code: exit 3
+",
+    )
+    .unwrap();
+
+    let mut progress = Vec::new();
+    cd.run_each(&ParseOptions::default(), |name, status| {
+        progress.push((name.to_string(), status.code()));
+    })
+    .unwrap();
+
+    assert_eq!(progress, [("Pass".to_string(), Some(0)), ("Fail".to_string(), Some(3))]);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn run_script_in_test() {
+    use std::{env, fs, io::ErrorKind};
+
+    use privacy_sexy::run_script_in;
+
+    let dir = env::temp_dir().join(format!("privacy-sexy-run-in-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let script = format!(
+        "#!/bin/sh\n[ \"$(dirname \"$0\")\" = '{}' ] && exit 5\nexit 1",
+        dir.display()
+    );
+    assert_eq!(run_script_in(&dir, &script, Some("sh".into())).unwrap().code(), Some(5));
+    assert!(fs::read_dir(&dir).unwrap().next().is_none());
+    fs::remove_dir_all(&dir).unwrap();
+
+    let err = run_script_in(&dir, "#!/bin/sh\nexit 0", None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(err.to_string().contains("is not an existing directory"));
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn missing_interpreter_test() {
+    use std::io::ErrorKind;
+
+    use privacy_sexy::run_script_with;
+
+    let err = run_script_with("exit 0", Some("ps1".into()), &["no-such-interpreter", "-File"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(err
+        .to_string()
+        .contains("interpreter `no-such-interpreter` may not be installed"));
+    assert!(err.to_string().contains(".ps1"));
+}
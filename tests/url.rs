@@ -0,0 +1,185 @@
+#![cfg(feature = "network")]
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+use privacy_sexy::collection::{CollectionData, CollectionError};
+
+const YAML: &str = "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo script
+";
+
+/// Serves each of `responses` to one connection in order, returning the received request heads
+fn serve<R: AsRef<[u8]> + Send + 'static>(responses: Vec<R>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/collection.yaml", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            stream.write_all(response.as_ref()).unwrap();
+            requests.push(request);
+        }
+        requests
+    });
+
+    (url, handle)
+}
+
+fn ok(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[test]
+fn from_url_cached_test() {
+    let cache_dir = env::temp_dir().join(format!("privacy-sexy-cache-test-{}", std::process::id()));
+    let (url, handle) = serve(vec![ok(YAML)]);
+
+    let fetched = CollectionData::from_url_cached(&url, &cache_dir, None).unwrap();
+    assert!(fetched.find_script("Script").is_some());
+    assert_eq!(handle.join().unwrap().len(), 1);
+
+    // Server is gone, so this must be served from the cache
+    let cached = CollectionData::from_url_cached(&url, &cache_dir, None).unwrap();
+    assert!(cached.find_script("Script").is_some());
+
+    // Cache files are named after the 64-bit FNV-1a hash of the URL, which must not change between releases
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let cache_file = cache_dir.join(format!("{hash:016x}.yaml"));
+    let exists = cache_file.is_file();
+
+    fs::remove_dir_all(cache_dir).unwrap();
+    assert!(exists);
+}
+
+#[test]
+fn from_url_cached_not_modified_test() {
+    let cache_dir = env::temp_dir().join(format!("privacy-sexy-etag-test-{}", std::process::id()));
+    let (url, handle) = serve(vec![
+        format!(
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{YAML}",
+            YAML.len()
+        ),
+        "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string(),
+    ]);
+
+    CollectionData::from_url_cached(&url, &cache_dir, Some(Duration::ZERO)).unwrap();
+    let revalidated = CollectionData::from_url_cached(&url, &cache_dir, Some(Duration::ZERO)).unwrap();
+    assert!(revalidated.find_script("Script").is_some());
+
+    let requests = handle.join().unwrap();
+    assert!(!requests[0].to_lowercase().contains("if-none-match"));
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+
+    fs::remove_dir_all(cache_dir).unwrap();
+}
+
+#[test]
+fn from_url_retry_test() {
+    let unavailable = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+    let (url, handle) = serve(vec![unavailable.clone(), unavailable, ok(YAML)]);
+
+    let cd = CollectionData::from_url_retry(&url, 3, Duration::from_millis(10)).unwrap();
+    assert!(cd.find_script("Script").is_some());
+    assert_eq!(handle.join().unwrap().len(), 3);
+
+    let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+    let (url, handle) = serve(vec![not_found]);
+
+    let err = CollectionData::from_url_retry(&url, 3, Duration::from_millis(10)).unwrap_err();
+    assert!(matches!(err, CollectionError::ReqwestError(e) if e.status().is_some_and(|s| s.as_u16() == 404)));
+    assert_eq!(handle.join().unwrap().len(), 1);
+}
+
+#[test]
+fn from_url_with_timeout_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/collection.yaml", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+        drop(stream);
+    });
+
+    let err = CollectionData::from_url_with_timeout(&url, Duration::from_millis(100)).unwrap_err();
+    assert!(matches!(err, CollectionError::ReqwestError(e) if e.is_timeout()));
+
+    handle.join().unwrap();
+}
+
+/// Responds with `body` encoded as `encoding`
+fn encoded(encoding: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: {encoding}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+#[test]
+fn from_url_compressed_test() {
+    let scripts: String = (0..20)
+        .map(|i| format!("      - name: Script {i}\n        code: echo script {i}\n"))
+        .collect();
+    let yaml = YAML.replace("      - name: Script\n        code: echo script\n", &scripts);
+    let mut gzipped = GzEncoder::new(Vec::new(), Compression::default());
+    gzipped.write_all(yaml.as_bytes()).unwrap();
+
+    let (url, handle) = serve(vec![encoded("gzip", &gzipped.finish().unwrap())]);
+    let cd = CollectionData::from_url(&url).unwrap();
+    assert_eq!(cd.iter_scripts().count(), 20);
+    assert!(cd.find_script("Script 19").is_some());
+    assert!(handle.join().unwrap()[0]
+        .to_lowercase()
+        .contains("accept-encoding: gzip, deflate"));
+
+    let mut deflated = ZlibEncoder::new(Vec::new(), Compression::default());
+    deflated.write_all(YAML.as_bytes()).unwrap();
+    let deflated = deflated.finish().unwrap();
+    let (url, _) = serve(vec![encoded("deflate", &deflated)]);
+    assert!(CollectionData::from_url(&url).unwrap().find_script("Script").is_some());
+
+    let (url, _) = serve(vec![encoded("gzip", YAML.as_bytes())]);
+    assert!(matches!(
+        CollectionData::from_url(&url),
+        Err(CollectionError::ReqwestError(_))
+    ));
+
+    let (url, _) = serve(vec![ok(YAML)]);
+    assert!(CollectionData::from_url(&url).unwrap().find_script("Script").is_some());
+}
@@ -0,0 +1,266 @@
+use privacy_sexy::collection::{CollectionData, ValidationError, ValidationReport};
+
+#[test]
+fn validate_bundled_test() {
+    for fpath in [
+        "collections/linux.yaml",
+        "collections/macos.yaml",
+        "collections/windows.yaml",
+    ] {
+        assert!(CollectionData::from_file(fpath).unwrap().validate().is_ok());
+    }
+}
+
+#[test]
+fn validate_duplicates_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo one
+      - category: Category
+        children:
+          - name: Script
+            call:
+              function: NoSuchFunction
+functions:
+  - name: Function
+    code: echo function
+  - name: Function
+    code: echo function
+",
+    )
+    .unwrap();
+
+    let errors = cd.validate().unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::DuplicateScript(name) if name == "Script")));
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::DuplicateCategory(name) if name == "Category")));
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::DuplicateFunction(name) if name == "Function")));
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::UnknownFunction(name) if name == "NoSuchFunction")));
+}
+
+#[test]
+fn validate_code_call_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Both
+        code: echo both
+        call:
+          function: Function
+      - name: Neither
+        docs: https://example.com
+      - name: Code
+        code: echo code
+      - name: Call
+        call:
+          function: Function
+functions:
+  - name: Function
+    code: echo function
+  - name: Empty function
+",
+    )
+    .unwrap();
+
+    let errors = cd.validate().unwrap_err();
+    let names = errors
+        .iter()
+        .map(|e| match e {
+            ValidationError::CodeCallConflict(name) => name.as_str(),
+            e => panic!("unexpected {e}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Both", "Neither", "Empty function"]);
+}
+
+#[test]
+fn validate_parameters_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Outer
+          parameters:
+            used: a
+            unused: b
+functions:
+  - name: Outer
+    parameters:
+      - name: used
+      - name: unused
+    call:
+      function: Inner
+      parameters:
+        text: '{{ $used }} {{ $undeclared }}'
+  - name: Inner
+    parameters:
+      - name: text
+    code: '{{ with $text }}echo {{ . }}{{ end }}'
+",
+    )
+    .unwrap();
+
+    let errors = cd.validate().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[0],
+        ValidationError::UnusedParameter(function, parameter) if function == "Outer" && parameter == "unused"
+    ));
+    assert!(matches!(
+        &errors[1],
+        ValidationError::UndeclaredParameter(function, parameter) if function == "Outer" && parameter == "undeclared"
+    ));
+}
+
+#[test]
+fn validate_doc_urls_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        code: echo code
+        docs:
+          - https://example.com/valid
+          - htps//example.com/malformed
+          - Markdown documentation referring to https://example.com
+",
+    )
+    .unwrap();
+
+    let errors = cd.validate().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        ValidationError::InvalidDocUrl { script, url } if script == "Script" && url == "htps//example.com/malformed"
+    ));
+}
+
+#[test]
+fn validation_report_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script B
+        code: echo b
+        docs: htps//b.example.com
+        call:
+          function: NoSuchFunction
+      - name: Script A
+        code: echo a
+        docs: htps//example.com
+      - name: Script A
+        code: echo a
+        docs: htps//example.com
+",
+    )
+    .unwrap();
+
+    let report = ValidationReport::from(cd.validate().unwrap_err()).to_string();
+    for section in [
+        "Duplicate scripts (1):\n",
+        "Unknown functions (1):\n",
+        "Code and call conflicts (1):\n",
+        "Invalid documentation URLs (3):\n",
+    ] {
+        assert!(report.contains(section), "{section:?} missing from {report}");
+    }
+    assert!(report.contains(
+        "Invalid documentation URLs (3):
+  - script `Script A` has invalid documentation URL `htps//example.com`
+  - script `Script A` has invalid documentation URL `htps//example.com`
+  - script `Script B` has invalid documentation URL `htps//b.example.com`
+"
+    ));
+}
+
+#[test]
+fn validate_call_parameters_test() {
+    let cd: CollectionData = serde_yaml::from_str(
+        "
+os: linux
+scripting:
+  language: shellscript
+  startCode: start
+  endCode: end
+actions:
+  - category: Category
+    children:
+      - name: Script
+        call:
+          function: Outer
+          parameters:
+            file: a
+functions:
+  - name: Outer
+    parameters:
+      - name: file
+    call:
+      function: Inner
+      parameters:
+        path: '{{ $file }}'
+  - name: Inner
+    parameters:
+      - name: path
+      - name: mode
+      - name: flags
+        optional: true
+      - name: owner
+        default: root
+    code: 'install -m {{ $mode }} -o {{ $owner }} {{ $path }}{{ with $flags }} {{ . }}{{ end }}'
+",
+    )
+    .unwrap();
+
+    let errors = cd.validate().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        ValidationError::MissingCallParameter { call, param } if call == "Inner" && param == "mode"
+    ));
+}